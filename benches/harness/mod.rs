@@ -0,0 +1,118 @@
+//! A small benchmark harness that runs offline, shared by the `lexer` and
+//! `lisp` benches. Each bench binary installs `CountingAllocator` as its
+//! global allocator so allocations per token can be reported next to the
+//! throughput.
+
+#![allow(dead_code)]
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::io::BufRead;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+/// Forwards to the system allocator, counting every allocation.
+pub struct CountingAllocator;
+
+unsafe impl GlobalAlloc for CountingAllocator {
+  #[inline]
+  unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+    ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+    System.alloc(layout)
+  }
+
+  #[inline]
+  unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+    System.dealloc(ptr, layout)
+  }
+
+  #[inline]
+  unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+    ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+    System.realloc(ptr, layout, new_size)
+  }
+}
+
+#[inline]
+fn allocations() -> usize {
+  ALLOCATIONS.load(Ordering::Relaxed)
+}
+
+/// Chars of a `BufRead` read a line at a time, to lex input that is never
+/// held in memory as a whole.
+pub struct StreamChars<R> {
+  reader: R,
+  line: Vec<char>,
+  index: usize,
+  buf: String,
+}
+
+impl<R> StreamChars<R>
+where
+  R: BufRead,
+{
+  #[inline]
+  pub fn new(reader: R) -> Self {
+    StreamChars {
+      reader,
+      line: Vec::new(),
+      index: 0,
+      buf: String::new(),
+    }
+  }
+}
+
+impl<R> Iterator for StreamChars<R>
+where
+  R: BufRead,
+{
+  type Item = char;
+
+  #[inline]
+  fn next(&mut self) -> Option<char> {
+    if self.index == self.line.len() {
+      self.buf.clear();
+      if self.reader.read_line(&mut self.buf).ok()? == 0 {
+        return None;
+      }
+      self.line.clear();
+      self.line.extend(self.buf.chars());
+      self.index = 0;
+    }
+
+    self.index += 1;
+    Some(self.line[self.index - 1])
+  }
+}
+
+/// Runs `f`, which lexes `bytes` of input and returns how many tokens it
+/// read, for about `TIME` after a warm up run, then prints the throughput
+/// and allocations per token.
+pub fn bench<F>(name: &str, bytes: usize, mut f: F)
+where
+  F: FnMut() -> usize,
+{
+  const TIME: Duration = Duration::from_millis(500);
+
+  let tokens = f();
+  let allocations_start = allocations();
+  let start = Instant::now();
+  let mut runs = 0;
+
+  while runs == 0 || start.elapsed() < TIME {
+    assert_eq!(f(), tokens, "{} read a different number of tokens", name);
+    runs += 1;
+  }
+
+  let elapsed = start.elapsed().as_secs_f64();
+  let allocations = (allocations() - allocations_start) as f64 / runs as f64;
+
+  println!(
+    "{:<32} {:>10.2} MB/s {:>10} tokens {:>8.2} allocs/token",
+    name,
+    (bytes * runs) as f64 / elapsed / 1_000_000.0,
+    tokens,
+    allocations / tokens.max(1) as f64,
+  );
+}
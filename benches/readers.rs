@@ -0,0 +1,97 @@
+extern crate lexer;
+
+#[path = "harness/mod.rs"]
+mod harness;
+
+use std::io::Cursor;
+
+use lexer::readers::{
+  IdentifierReader, LineCommentReader, NumberLiteral, NumberReader, ReadError, StringReader,
+  WhitespaceReader,
+};
+use lexer::{char_class, Readers, ReadersBuilder, Token, TokenMeta};
+
+use harness::{bench, CountingAllocator, StreamChars};
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+#[derive(Clone, Debug, PartialEq)]
+enum Value {
+  Identifier(String),
+  Number(NumberLiteral),
+  String(String),
+  Punct(String),
+}
+
+fn readers() -> Readers<Token<Value>, ReadError> {
+  ReadersBuilder::new()
+    .add(WhitespaceReader::new())
+    .add(LineCommentReader::new("//"))
+    .add(NumberReader::new(
+      |literal: NumberLiteral, meta| Token::new(meta, Value::Number(literal)),
+      |error, _: TokenMeta| error,
+    ))
+    .add(StringReader::new(
+      |string, meta| Token::new(meta, Value::String(string)),
+      |error, _: TokenMeta| error,
+    ))
+    .add(IdentifierReader::new(|string, meta| {
+      Token::new(meta, Value::Identifier(string))
+    }))
+    .add(char_class(
+      |ch: char| !ch.is_alphanumeric() && !ch.is_whitespace(),
+      |string, meta| Token::new(meta, Value::Punct(string)),
+    ))
+    .build()
+}
+
+fn code(lines: usize) -> String {
+  (0..lines)
+    .map(|line| {
+      format!(
+        "let value_{} = call(value, {}.5, \"text {}\"); // note {}\n",
+        line, line, line, line
+      )
+    })
+    .collect()
+}
+
+fn long_strings(count: usize, len: usize) -> String {
+  (0..count)
+    .map(|index| format!("\"{}\\n{}\"\n", "a".repeat(len), index))
+    .collect()
+}
+
+fn whitespace_runs(count: usize, len: usize) -> String {
+  (0..count)
+    .map(|index| format!("x{}{}", index, " \t\n".repeat(len / 3)))
+    .collect()
+}
+
+fn unicode(lines: usize) -> String {
+  (0..lines)
+    .map(|line| format!("変数{} = «значение» + 😀 {} ; ünïcödé\n", line, line))
+    .collect()
+}
+
+fn main() {
+  let readers = readers();
+  let corpora = [
+    ("code", code(20_000)),
+    ("long strings", long_strings(200, 10_000)),
+    ("whitespace runs", whitespace_runs(200, 10_000)),
+    ("unicode", unicode(20_000)),
+  ];
+
+  for &(name, ref source) in &corpora {
+    bench(&format!("{} &str", name), source.len(), || {
+      readers.read(source.chars()).count()
+    });
+    bench(&format!("{} stream", name), source.len(), || {
+      readers
+        .read(StreamChars::new(Cursor::new(source.as_bytes())))
+        .count()
+    });
+  }
+}
@@ -0,0 +1,81 @@
+extern crate lexer;
+
+use std::mem;
+use std::time::Instant;
+
+use lexer::readers::{IdentifierReader, WhitespaceReader};
+use lexer::{char_class, Readers, ReadersBuilder, Token, TokenBuffer};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Kind {
+  Identifier,
+  Number,
+  Punct,
+}
+
+fn readers() -> Readers<Token<Kind>, ()> {
+  ReadersBuilder::new()
+    .add(WhitespaceReader::new())
+    .add(IdentifierReader::new(|_, meta| {
+      Token::new(meta, Kind::Identifier)
+    }))
+    .add(char_class(
+      |ch: char| ch.is_ascii_digit(),
+      |_, meta| Token::new(meta, Kind::Number),
+    ))
+    .add(char_class(
+      |ch: char| !ch.is_alphanumeric() && !ch.is_whitespace(),
+      |_, meta| Token::new(meta, Kind::Punct),
+    ))
+    .build()
+}
+
+fn source(lines: usize) -> String {
+  (0..lines)
+    .map(|line| {
+      format!(
+        "let value_{} = call(value, {}) + {};\n",
+        line,
+        line * 7,
+        line % 13
+      )
+    })
+    .collect()
+}
+
+fn main() {
+  let readers = readers();
+
+  println!(
+    "{:>10} {:>10} {:>14} {:>14} {:>8} {:>12}",
+    "lines", "tokens", "Vec<Token>", "TokenBuffer", "ratio", "rebuild ms"
+  );
+
+  for &lines in &[1_000, 10_000, 100_000] {
+    let source = source(lines);
+    let tokens: Vec<Token<Kind>> = readers
+      .read(source.chars())
+      .map(|token| token.expect("valid token"))
+      .collect();
+    let vec_size = tokens.len() * mem::size_of::<Token<Kind>>();
+
+    let mut buffer = TokenBuffer::new(&source);
+    buffer.extend(tokens.iter().cloned());
+    buffer.shrink_to_fit();
+
+    let start = Instant::now();
+    let rebuilt = buffer.tokens(&source, |&kind, _| kind).count();
+    let elapsed = start.elapsed();
+
+    assert_eq!(rebuilt, tokens.len());
+    println!(
+      "{:>10} {:>10} {:>14} {:>14} {:>7.1}x {:>12.2}",
+      lines,
+      tokens.len(),
+      vec_size,
+      buffer.heap_size(),
+      vec_size as f64 / buffer.heap_size() as f64,
+      elapsed.as_secs_f64() * 1000.0
+    );
+  }
+}
@@ -0,0 +1,233 @@
+use proc_macro2::{Literal, TokenStream};
+use regex_automata::dfa::{dense, StartKind};
+use regex_automata::MatchKind;
+use syn::punctuated::Punctuated;
+use syn::{Attribute, Data, DeriveInput, Error, Expr, ExprLit, Fields, Lit, LitStr, Token, Type};
+
+enum Kind {
+  Token,
+  Regex,
+  Skip,
+}
+
+struct Rule {
+  kind: Kind,
+  pattern: LitStr,
+  callback: Option<Expr>,
+  dfa: Option<dense::DFA<Vec<u32>>>,
+}
+
+pub fn expand(input: &DeriveInput) -> Result<TokenStream, Error> {
+  let data = match input.data {
+    Data::Enum(ref data) => data,
+    _ => {
+      return Err(Error::new_spanned(
+        input,
+        "Lexer can only be derived for enums",
+      ))
+    }
+  };
+
+  let name = &input.ident;
+  let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+  let error_type = error_type(&input.attrs)?;
+  let mut rules = Vec::new();
+
+  for rule in parse_rules(&input.attrs)? {
+    match rule.kind {
+      Kind::Skip => rules.push(skip_rule(&rule)),
+      _ => {
+        return Err(Error::new_spanned(
+          &rule.pattern,
+          "only #[skip] can be used on the enum itself",
+        ))
+      }
+    }
+  }
+
+  for variant in data.variants.iter() {
+    let ident = &variant.ident;
+    let error = format!("invalid {}", ident);
+
+    for rule in parse_rules(&variant.attrs)? {
+      if let Kind::Skip = rule.kind {
+        rules.push(skip_rule(&rule));
+        continue;
+      }
+
+      let pattern = pattern(&rule);
+      let constructor = match variant.fields {
+        Fields::Unit => match rule.callback {
+          Some(ref callback) => {
+            return Err(Error::new_spanned(
+              callback,
+              "unit variants cannot have a callback",
+            ))
+          }
+          None => quote!(|_: &str| Ok(#name::#ident)),
+        },
+        Fields::Unnamed(ref fields) if fields.unnamed.len() == 1 => {
+          let value = value(&rule, &error);
+          quote!(|string: &str| #value.map(#name::#ident))
+        }
+        Fields::Named(ref fields) if fields.named.len() == 1 => {
+          let field = &fields.named[0].ident;
+          let value = value(&rule, &error);
+          quote!(|string: &str| #value.map(|value| #name::#ident { #field: value }))
+        }
+        _ => {
+          return Err(Error::new_spanned(
+            variant,
+            "Lexer variants must be units or have a single field",
+          ))
+        }
+      };
+
+      rules.push(quote!(.token(#pattern, #constructor)));
+    }
+  }
+
+  Ok(quote! {
+    impl #impl_generics ::lexer::Lexer for #name #ty_generics #where_clause {
+      type Error = #error_type;
+
+      #[inline]
+      fn readers() -> ::lexer::Readers<::lexer::Token<Self>, ::lexer::TokenError<Self::Error>> {
+        ::lexer::ReadersBuilder::new()
+          .add(::lexer::PatternReader::<Self, Self::Error>::new()#(#rules)*)
+          .build()
+      }
+    }
+  })
+}
+
+/// The type given with `#[lexer(error = Type)]`, `&'static str` by default.
+/// It has to implement `From<&'static str>` for the errors the derive makes.
+fn error_type(attrs: &[Attribute]) -> Result<TokenStream, Error> {
+  let mut error_type = None;
+
+  for attr in attrs {
+    if attr.path().is_ident("lexer") {
+      attr.parse_nested_meta(|meta| {
+        if meta.path.is_ident("error") {
+          error_type = Some(meta.value()?.parse::<Type>()?);
+          Ok(())
+        } else {
+          Err(meta.error("expected `error = Type`"))
+        }
+      })?;
+    }
+  }
+
+  Ok(match error_type {
+    Some(error_type) => quote!(#error_type),
+    None => quote!(&'static str),
+  })
+}
+
+fn parse_rules(attrs: &[Attribute]) -> Result<Vec<Rule>, Error> {
+  let mut rules = Vec::new();
+
+  for attr in attrs {
+    let kind = if attr.path().is_ident("token") {
+      Kind::Token
+    } else if attr.path().is_ident("regex") {
+      Kind::Regex
+    } else if attr.path().is_ident("skip") {
+      Kind::Skip
+    } else {
+      continue;
+    };
+
+    let args = attr.parse_args_with(Punctuated::<Expr, Token![,]>::parse_terminated)?;
+    let mut args = args.into_iter();
+
+    let pattern = match args.next() {
+      Some(Expr::Lit(ExprLit {
+        lit: Lit::Str(pattern),
+        ..
+      })) => pattern,
+      _ => return Err(Error::new_spanned(attr, "expected a string pattern")),
+    };
+    let callback = args.next();
+
+    if let Some(extra) = args.next() {
+      return Err(Error::new_spanned(extra, "unexpected argument"));
+    }
+    if let (Kind::Skip, Some(ref callback)) = (&kind, &callback) {
+      return Err(Error::new_spanned(callback, "#[skip] takes no callback"));
+    }
+    let dfa = if let Kind::Token = kind {
+      if pattern.value().is_empty() {
+        return Err(Error::new_spanned(pattern, "token cannot be empty"));
+      }
+      None
+    } else {
+      Some(build_regex(&pattern)?)
+    };
+
+    rules.push(Rule {
+      kind,
+      pattern,
+      callback,
+      dfa,
+    });
+  }
+
+  Ok(rules)
+}
+
+/// Builds the DFA at compile time with the same config `Pattern::regex` uses,
+/// so `readers()` only has to load it. Its start states cover every look
+/// behind, `Pattern` picks one from the char before each token.
+fn build_regex(pattern: &LitStr) -> Result<dense::DFA<Vec<u32>>, Error> {
+  dense::Builder::new()
+    .configure(
+      dense::Config::new()
+        .start_kind(StartKind::Anchored)
+        .match_kind(MatchKind::All),
+    )
+    .build(&pattern.value())
+    .map_err(|error| Error::new_spanned(pattern, error))
+}
+
+fn pattern(rule: &Rule) -> TokenStream {
+  let pattern = &rule.pattern;
+
+  match rule.dfa {
+    None => quote!(::lexer::Pattern::literal(#pattern)),
+    Some(ref dfa) => {
+      let (little, pad) = dfa.to_bytes_little_endian();
+      let little = Literal::byte_string(&little[pad..]);
+      let (big, pad) = dfa.to_bytes_big_endian();
+      let big = Literal::byte_string(&big[pad..]);
+
+      quote!({
+        #[repr(C)]
+        struct Aligned<B: ?Sized> {
+          _align: [u32; 0],
+          bytes: B,
+        }
+
+        #[cfg(target_endian = "little")]
+        static DFA: &Aligned<[u8]> = &Aligned { _align: [], bytes: *#little };
+        #[cfg(target_endian = "big")]
+        static DFA: &Aligned<[u8]> = &Aligned { _align: [], bytes: *#big };
+
+        ::lexer::Pattern::compiled(#pattern, &DFA.bytes).expect("invalid regex")
+      })
+    }
+  }
+}
+
+fn skip_rule(rule: &Rule) -> TokenStream {
+  let pattern = pattern(rule);
+  quote!(.skip(#pattern))
+}
+
+fn value(rule: &Rule, error: &str) -> TokenStream {
+  match rule.callback {
+    Some(ref callback) => quote!((#callback)(string).ok_or_else(|| #error.into())),
+    None => quote!(string.parse().map_err(|_| #error.into())),
+  }
+}
@@ -0,0 +1,30 @@
+extern crate proc_macro;
+extern crate proc_macro2;
+#[macro_use]
+extern crate quote;
+extern crate regex_automata;
+extern crate syn;
+
+mod lexer;
+mod token_kind;
+
+use proc_macro::TokenStream;
+use syn::{parse_macro_input, DeriveInput};
+
+#[proc_macro_derive(TokenKind)]
+pub fn derive_token_kind(input: TokenStream) -> TokenStream {
+  let input = parse_macro_input!(input as DeriveInput);
+
+  token_kind::expand(&input)
+    .unwrap_or_else(syn::Error::into_compile_error)
+    .into()
+}
+
+#[proc_macro_derive(Lexer, attributes(lexer, token, regex, skip))]
+pub fn derive_lexer(input: TokenStream) -> TokenStream {
+  let input = parse_macro_input!(input as DeriveInput);
+
+  lexer::expand(&input)
+    .unwrap_or_else(syn::Error::into_compile_error)
+    .into()
+}
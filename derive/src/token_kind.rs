@@ -0,0 +1,50 @@
+use proc_macro2::TokenStream;
+use syn::{Data, DeriveInput, Error, Fields};
+
+pub fn expand(input: &DeriveInput) -> Result<TokenStream, Error> {
+  let data = match input.data {
+    Data::Enum(ref data) => data,
+    _ => {
+      return Err(Error::new_spanned(
+        input,
+        "TokenKind can only be derived for enums",
+      ))
+    }
+  };
+
+  let name = &input.ident;
+  let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+  let mut id_arms = Vec::new();
+  let mut name_arms = Vec::new();
+
+  for (index, variant) in data.variants.iter().enumerate() {
+    let ident = &variant.ident;
+    let pattern = match variant.fields {
+      Fields::Named(_) => quote!(#name::#ident { .. }),
+      Fields::Unnamed(_) => quote!(#name::#ident(..)),
+      Fields::Unit => quote!(#name::#ident),
+    };
+    let kind_name = ident.to_string();
+
+    id_arms.push(quote!(#pattern => #index,));
+    name_arms.push(quote!(#pattern => #kind_name,));
+  }
+
+  Ok(quote! {
+    impl #impl_generics ::lexer::TokenKind for #name #ty_generics #where_clause {
+      #[inline]
+      fn kind_id(&self) -> usize {
+        match *self {
+          #(#id_arms)*
+        }
+      }
+      #[inline]
+      fn kind_name(&self) -> &'static str {
+        match *self {
+          #(#name_arms)*
+        }
+      }
+    }
+  })
+}
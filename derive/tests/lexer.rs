@@ -0,0 +1,131 @@
+extern crate lexer;
+
+use lexer::{Lexer, Token};
+
+fn parse_hex(string: &str) -> Option<u32> {
+  u32::from_str_radix(&string[2..], 16).ok()
+}
+
+#[derive(Debug, PartialEq, Lexer)]
+#[skip("[ \t\r\n]+")]
+enum Value {
+  #[token("==")]
+  EqEq,
+  #[token("=")]
+  Eq,
+  #[token("if")]
+  If,
+  #[regex("0x[0-9a-fA-F]+", parse_hex)]
+  Hex(u32),
+  #[regex("[0-9]+")]
+  Number(u8),
+  #[regex("[a-zA-Z_][a-zA-Z0-9_]*", |s: &str| Some(s.to_owned()))]
+  Identifier { name: String },
+  #[allow(dead_code)]
+  #[skip("#[^\n]*")]
+  Comment,
+}
+
+fn lex(string: &str) -> Vec<Result<Value, &'static str>> {
+  Value::readers()
+    .read(string.chars())
+    .map(|result| {
+      result
+        .map(Token::into_value)
+        .map_err(|error| *error.error())
+    })
+    .collect()
+}
+
+#[test]
+fn test_derive_lexer() {
+  assert_eq!(
+    lex("if iffy == 0xff # comment\n= 42"),
+    [
+      Ok(Value::If),
+      Ok(Value::Identifier {
+        name: "iffy".into()
+      }),
+      Ok(Value::EqEq),
+      Ok(Value::Hex(255)),
+      Ok(Value::Eq),
+      Ok(Value::Number(42)),
+    ]
+  );
+}
+
+#[test]
+fn test_derive_lexer_errors() {
+  assert_eq!(
+    lex("256 $"),
+    [Err("invalid Number"), Err("unexpected character")]
+  );
+}
+
+#[test]
+fn test_derive_lexer_meta() {
+  let tokens: Vec<Token<Value>> = Value::readers()
+    .read("a\n  bc".chars())
+    .map(Result::unwrap)
+    .collect();
+
+  assert_eq!(tokens[1].meta().line_start(), 2);
+  assert_eq!(tokens[1].meta().col_start(), 3);
+  assert_eq!(tokens[1].meta().col_end(), 5);
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum LexError {
+  Unexpected,
+  Other(&'static str),
+}
+
+impl From<&'static str> for LexError {
+  fn from(error: &'static str) -> Self {
+    match error {
+      "unexpected character" => LexError::Unexpected,
+      error => LexError::Other(error),
+    }
+  }
+}
+
+#[derive(Debug, PartialEq, Lexer)]
+#[lexer(error = LexError)]
+#[skip(" +")]
+enum Op {
+  #[regex("<|<=|<<=")]
+  Less,
+  #[regex("[0-9]+")]
+  Number(u8),
+}
+
+#[test]
+fn test_derive_lexer_error_type() {
+  let values: Vec<Result<Op, LexError>> = Op::readers()
+    .read("< 300 ?".chars())
+    .map(|result| {
+      result
+        .map(Token::into_value)
+        .map_err(|error| error.error().clone())
+    })
+    .collect();
+
+  assert_eq!(
+    values,
+    [
+      Ok(Op::Less),
+      Err(LexError::Other("invalid Number")),
+      Err(LexError::Unexpected)
+    ]
+  );
+}
+
+#[test]
+fn test_derive_lexer_longest_regex_match() {
+  let lens: Vec<u64> = Op::readers()
+    .read("<<= <= <".chars())
+    .map(|result| result.unwrap().meta().len())
+    .collect();
+
+  assert_eq!(lens, [3, 2, 1]);
+}
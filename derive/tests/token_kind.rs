@@ -0,0 +1,27 @@
+extern crate lexer;
+
+use lexer::{Token, TokenKind, TokenMeta};
+
+#[allow(dead_code)]
+#[derive(TokenKind)]
+enum Value {
+  Number(i64),
+  Identifier { name: String },
+  Eof,
+}
+
+#[test]
+fn test_derive_token_kind() {
+  let number = Value::Number(1);
+  let identifier = Value::Identifier { name: "a".into() };
+
+  assert_eq!(number.kind_id(), 0);
+  assert_eq!(number.kind_name(), "Number");
+  assert_eq!(identifier.kind_id(), 1);
+  assert_eq!(identifier.kind_name(), "Identifier");
+  assert_eq!(Value::Eof.kind_id(), 2);
+  assert_eq!(Value::Eof.kind_name(), "Eof");
+
+  let token = Token::new(TokenMeta::new(0, 1, 1, 2, 1, 1), Value::Eof);
+  assert_eq!(token.kind_name(), "Eof");
+}
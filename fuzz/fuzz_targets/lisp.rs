@@ -0,0 +1,16 @@
+#![no_main]
+
+#[macro_use]
+extern crate libfuzzer_sys;
+extern crate lexer;
+extern crate lisp;
+
+fuzz_target!(|data: &[u8]| {
+  if let Ok(input) = std::str::from_utf8(data) {
+    let readers = lisp::readers();
+
+    if let Err(violation) = lexer::testing::check(&readers, input, false) {
+      panic!("{} in {:?}", violation, input);
+    }
+  }
+});
@@ -0,0 +1,50 @@
+#![no_main]
+
+#[macro_use]
+extern crate libfuzzer_sys;
+extern crate lexer;
+
+use lexer::readers::{
+  BlockCommentReader, IdentifierReader, LineCommentReader, NumberLiteral, NumberReader, ReadError,
+  StringReader, WhitespaceReader,
+};
+use lexer::{Readers, ReadersBuilder, Token, TokenError, TokenMeta};
+
+type Value = Option<NumberLiteral>;
+
+fn readers() -> Readers<Token<Value>, TokenError<ReadError>> {
+  ReadersBuilder::new()
+    .add(WhitespaceReader::new())
+    .add(LineCommentReader::new("//"))
+    .add(BlockCommentReader::new("/*", "*/", to_error).nested(true))
+    .add(
+      NumberReader::new(|literal, meta| Token::new(meta, Some(literal)), to_error)
+        .signed(true)
+        .suffixes(true)
+        .big_integers(true),
+    )
+    .add(StringReader::new(
+      |_, meta| Token::new(meta, None),
+      to_error,
+    ))
+    .add(IdentifierReader::new(|_, meta| Token::new(meta, None)))
+    .add_fn(|_, input, current, next| match input.read(next) {
+      Some(_) => {
+        lexer::ReaderResult::Some(Token::new(TokenMeta::new_state_meta(current, next), None))
+      }
+      None => lexer::ReaderResult::None,
+    })
+    .build()
+}
+
+fn to_error(error: ReadError, meta: TokenMeta) -> TokenError<ReadError> {
+  TokenError::new(meta, error)
+}
+
+fuzz_target!(|data: &[u8]| {
+  if let Ok(input) = std::str::from_utf8(data) {
+    if let Err(violation) = lexer::testing::check(&readers(), input, false) {
+      panic!("{} in {:?}", violation, input);
+    }
+  }
+});
@@ -0,0 +1,70 @@
+extern crate lisp;
+
+#[path = "../../benches/harness/mod.rs"]
+mod harness;
+
+use std::io::Cursor;
+
+use harness::{bench, CountingAllocator, StreamChars};
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+fn nested(count: usize, depth: usize) -> String {
+  let mut source = String::new();
+
+  for index in 0..count {
+    for _ in 0..depth {
+      source.push_str("(f ");
+    }
+    source.push_str(&index.to_string());
+    for _ in 0..depth {
+      source.push(')');
+    }
+    source.push('\n');
+  }
+  source
+}
+
+fn flat(count: usize) -> String {
+  (0..count)
+    .map(|index| {
+      format!(
+        "(def-fn name-{} (a b) (println :key-{} \"value {}\" 0x{:x} -{}))\n",
+        index, index, index, index, index
+      )
+    })
+    .collect()
+}
+
+fn count(tokens: &[lisp::Token]) -> usize {
+  tokens
+    .iter()
+    .map(|token| match *token.value() {
+      lisp::TokenValue::List(ref list) => 1 + count(list),
+      _ => 1,
+    })
+    .sum()
+}
+
+fn main() {
+  let readers = lisp::readers();
+  let corpora = [
+    ("lisp deep nesting", nested(200, 200)),
+    ("lisp flat", flat(20_000)),
+  ];
+
+  for &(name, ref source) in &corpora {
+    bench(&format!("{} &str", name), source.len(), || {
+      let tokens: Vec<_> = readers.read(source.chars()).map(Result::unwrap).collect();
+      count(&tokens)
+    });
+    bench(&format!("{} stream", name), source.len(), || {
+      let tokens: Vec<_> = readers
+        .read(StreamChars::new(Cursor::new(source.as_bytes())))
+        .map(Result::unwrap)
+        .collect();
+      count(&tokens)
+    });
+  }
+}
@@ -2,11 +2,14 @@ extern crate lexer;
 
 use std::fmt::{self, Write};
 
+use lexer::readers::{
+  Number, NumberLiteral, NumberReader, ReadError, StringReader, WhitespaceReader,
+};
 use lexer::{Input, Reader, ReaderResult, Readers, ReadersBuilder, State, TokenMeta};
 
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
 pub enum TokenValue {
-  Number(isize),
+  Number(i64),
   String(String),
   Keyword(String),
   Identifier(String),
@@ -16,11 +19,11 @@ pub enum TokenValue {
 impl fmt::Display for TokenValue {
   fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
     match self {
-      &TokenValue::Number(ref n) => write!(f, "{}", n),
-      &TokenValue::String(ref s) => write!(f, "{:?}", s),
-      &TokenValue::Keyword(ref s) => write!(f, ":{}", s),
-      &TokenValue::Identifier(ref s) => write!(f, "{}", s),
-      &TokenValue::List(ref list) => {
+      TokenValue::Number(n) => write!(f, "{}", n),
+      TokenValue::String(s) => write!(f, "{:?}", s),
+      TokenValue::Keyword(s) => write!(f, ":{}", s),
+      TokenValue::Identifier(s) => write!(f, "{}", s),
+      TokenValue::List(list) => {
         f.write_char('(')?;
 
         let mut index = 0;
@@ -43,115 +46,6 @@ impl fmt::Display for TokenValue {
 pub type Token = lexer::Token<TokenValue>;
 pub type TokenError = lexer::TokenError<&'static str>;
 
-#[derive(Debug, Clone, Eq, PartialEq, Hash)]
-pub struct WhitespaceReader;
-
-impl Reader<Token, TokenError> for WhitespaceReader {
-  fn read(
-    &self,
-    _: &Readers<Token, TokenError>,
-    input: &mut dyn Input,
-    _: &State,
-    next: &mut State,
-  ) -> ReaderResult<Token, TokenError> {
-    match input.read(next) {
-      Some(ch) => {
-        if is_whitespace(ch) {
-          while let Some(ch) = input.peek(next, 0) {
-            if is_whitespace(ch) {
-              input.read(next);
-            } else {
-              break;
-            }
-          }
-
-          ReaderResult::Empty
-        } else {
-          ReaderResult::None
-        }
-      }
-      None => ReaderResult::None,
-    }
-  }
-}
-
-#[derive(Debug, Clone, Eq, PartialEq, Hash)]
-pub struct NumberReader;
-
-impl Reader<Token, TokenError> for NumberReader {
-  fn read(
-    &self,
-    _: &Readers<Token, TokenError>,
-    input: &mut dyn Input,
-    current: &State,
-    next: &mut State,
-  ) -> ReaderResult<Token, TokenError> {
-    match input.read(next) {
-      Some(ch) => {
-        if ch.is_numeric() || ch == '-' {
-          let mut string = String::new();
-
-          string.push(ch);
-
-          while let Some(ch) = input.peek(next, 0) {
-            if ch.is_numeric() || ch == '_' {
-              input.read(next);
-              string.push(ch);
-            } else {
-              break;
-            }
-          }
-
-          ReaderResult::Some(Token::new(
-            TokenMeta::new_state_meta(current, next),
-            TokenValue::Number(string.parse().unwrap()),
-          ))
-        } else {
-          ReaderResult::None
-        }
-      }
-      None => ReaderResult::None,
-    }
-  }
-}
-
-#[derive(Debug, Clone, Eq, PartialEq, Hash)]
-pub struct StringReader;
-
-impl Reader<Token, TokenError> for StringReader {
-  fn read(
-    &self,
-    _: &Readers<Token, TokenError>,
-    input: &mut dyn Input,
-    current: &State,
-    next: &mut State,
-  ) -> ReaderResult<Token, TokenError> {
-    match input.read(next) {
-      Some(ch) => {
-        if ch == '"' {
-          let mut string = String::new();
-
-          while let Some(ch) = input.read(next) {
-            if ch == '"' {
-              break;
-            } else {
-              string.push(ch);
-            }
-          }
-
-          ReaderResult::Some(Token::new(
-            TokenMeta::new_state_meta(current, next),
-            TokenValue::String(string),
-          ))
-        } else {
-          ReaderResult::None
-        }
-      }
-      None => ReaderResult::None,
-    }
-  }
-}
-
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
 pub struct KeywordReader;
 
@@ -188,6 +82,10 @@ impl Reader<Token, TokenError> for KeywordReader {
       None => ReaderResult::None,
     }
   }
+
+  fn samples(&self) -> Vec<String> {
+    vec![":a".into()]
+  }
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
@@ -204,24 +102,30 @@ impl Reader<Token, TokenError> for ListReader {
     match input.read(next) {
       Some(ch) => {
         if ch == '(' {
+          let opened = next.clone();
           let mut list = Vec::new();
 
-          while let Some(ch) = input.peek(next, 0) {
-            if ch == ')' {
+          loop {
+            if input.peek(next, 0) == Some(')') {
               input.read(next);
               break;
-            } else {
-              match lexer::read(readers, input, next) {
-                Some(Ok(token)) => {
-                  list.push(token);
-                }
-                Some(Err(error)) => {
-                  return ReaderResult::Err(error);
-                }
-                _ => {
-                  break;
+            }
+
+            match lexer::read_many(readers, input, next) {
+              Some(results) => {
+                for result in results {
+                  match result {
+                    Ok(token) => list.push(token),
+                    Err(error) => return ReaderResult::Err(error),
+                  }
                 }
               }
+              None => {
+                return ReaderResult::Err(TokenError::new(
+                  TokenMeta::new_state_meta(current, &opened),
+                  "unterminated list",
+                ));
+              }
             }
           }
 
@@ -236,6 +140,10 @@ impl Reader<Token, TokenError> for ListReader {
       None => ReaderResult::None,
     }
   }
+
+  fn samples(&self) -> Vec<String> {
+    vec!["(a)".into()]
+  }
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
@@ -272,6 +180,10 @@ impl Reader<Token, TokenError> for IdentifierReader {
       None => ReaderResult::None,
     }
   }
+
+  fn samples(&self) -> Vec<String> {
+    vec!["a".into()]
+  }
 }
 
 #[inline]
@@ -283,14 +195,39 @@ fn is_closer(ch: char) -> bool {
   ch == ')'
 }
 
+fn to_number(literal: NumberLiteral, meta: TokenMeta) -> Token {
+  match literal.into_value() {
+    Number::Integer(n) => Token::new(meta, TokenValue::Number(n)),
+    _ => unreachable!("floats and big integers are disabled"),
+  }
+}
+
+fn to_string(string: String, meta: TokenMeta) -> Token {
+  Token::new(meta, TokenValue::String(string))
+}
+
+fn to_error(error: ReadError, meta: TokenMeta) -> TokenError {
+  TokenError::new(meta, error.as_str())
+}
+
 pub fn readers() -> lexer::Readers<Token, TokenError> {
   ReadersBuilder::new()
-    .add(WhitespaceReader)
-    .add(NumberReader)
-    .add(StringReader)
-    .add(KeywordReader)
-    .add(ListReader)
-    .add(IdentifierReader)
+    .add_named(
+      "Whitespace",
+      WhitespaceReader::with_predicate(is_whitespace),
+    )
+    .add_named(
+      "Number",
+      NumberReader::new(to_number, to_error)
+        .signed(true)
+        .floats(false),
+    )
+    .add_named("String", StringReader::new(to_string, to_error))
+    .add_named("Keyword", KeywordReader)
+    .add_named("List", ListReader)
+    // reads anything, so it goes after every other reader
+    .add_named("Identifier", IdentifierReader)
+    .priority(-1)
     .build()
 }
 
@@ -305,16 +242,133 @@ fn test_read() {
 
   assert_eq!(tokens.len(), 1);
 
-  if let Some(&TokenValue::List(ref tokens)) = tokens.get(0).map(Token::value) {
+  if let Some(TokenValue::List(tokens)) = tokens.first().map(Token::value) {
     let first = tokens.first().unwrap();
-    assert_eq!(first.meta().col_start(), 1);
-    assert_eq!(first.meta().col_end(), 7);
+    assert_eq!(first.meta().col_start(), 2);
+    assert_eq!(first.meta().col_end(), 8);
     assert_eq!(first.meta().col_count(), 6);
     assert_eq!(first.meta().line_start(), 1);
     assert_eq!(first.meta().line_end(), 1);
     assert_eq!(first.meta().line_count(), 0);
     assert_eq!(first.meta().len(), 6);
   } else {
-    assert!(false);
+    panic!("expected a list token");
+  }
+}
+
+#[test]
+fn test_read_numbers() {
+  let readers = readers();
+
+  let tokens: Vec<Token> = readers
+    .read("(- -1 0x1f \"a\\\"b\")".chars())
+    .map(Result::unwrap)
+    .collect();
+
+  if let Some(TokenValue::List(tokens)) = tokens.first().map(Token::value) {
+    let values: Vec<&TokenValue> = tokens.iter().map(Token::value).collect();
+
+    assert_eq!(
+      values,
+      [
+        &TokenValue::Identifier("-".into()),
+        &TokenValue::Number(-1),
+        &TokenValue::Number(31),
+        &TokenValue::String("a\"b".into()),
+      ]
+    );
+  } else {
+    panic!("expected a list token");
+  }
+}
+
+#[test]
+fn test_read_string_errors() {
+  let readers = readers();
+
+  let errors: Vec<TokenError> = readers
+    .read("\"a\\u{zz}b\" \"abc".chars())
+    .filter_map(Result::err)
+    .collect();
+
+  assert_eq!(errors.len(), 2);
+  assert_eq!(errors[0].error(), &"invalid escape");
+  assert_eq!(errors[0].meta().col_start(), 3);
+  assert_eq!(errors[0].meta().col_end(), 9);
+  assert_eq!(errors[1].error(), &"unterminated string");
+  assert_eq!(errors[1].meta().col_start(), 12);
+}
+
+#[test]
+fn test_read_unterminated_list() {
+  let readers = readers();
+
+  let errors: Vec<TokenError> = readers
+    .read("(a (b)".chars())
+    .filter_map(Result::err)
+    .collect();
+
+  assert_eq!(errors.len(), 1);
+  assert_eq!(errors[0].error(), &"unterminated list");
+  assert_eq!(errors[0].meta().col_start(), 1);
+  assert_eq!(errors[0].meta().col_end(), 2);
+}
+
+#[test]
+fn test_invariants() {
+  let readers = readers();
+
+  lexer::testing::check_random(
+    &readers,
+    &[
+      "(", ")", "-", "+", "0", "1", "0x", "f", "\"", "\\", "\\u{", ":", "a", " ", ",", "\n", "é",
+    ],
+    0,
+    1_000,
+    false,
+  );
+
+  for entry in
+    std::fs::read_dir(concat!(env!("CARGO_MANIFEST_DIR"), "/../fuzz/corpus/lisp")).unwrap()
+  {
+    let input = std::fs::read_to_string(entry.unwrap().path()).unwrap();
+
+    if let Err(violation) = lexer::testing::check(&readers, &input, false) {
+      panic!("{} in {:?}", violation, input);
+    }
   }
 }
+
+#[test]
+fn test_read_snapshot() {
+  let readers = readers();
+
+  let source = "(def-fn hello ()\n  (println :Hello, \"World!\" -1 0x1f))\n:done \"\\q\" (";
+  let output = lexer::testing::render(&readers, source, |value| match value {
+    TokenValue::List(list) => format!("list({})", list.len()),
+    TokenValue::Number(n) => format!("number({})", n),
+    TokenValue::String(s) => format!("string({:?})", s),
+    TokenValue::Keyword(s) => format!("keyword({})", s),
+    TokenValue::Identifier(s) => format!("identifier({})", s),
+  });
+
+  lexer::testing::assert_snapshot(
+    concat!(env!("CARGO_MANIFEST_DIR"), "/snapshots/read.snap"),
+    &output,
+  );
+}
+
+#[test]
+fn test_reader_names() {
+  assert_eq!(
+    readers().names().collect::<Vec<_>>(),
+    [
+      "Whitespace",
+      "Number",
+      "String",
+      "Keyword",
+      "List",
+      "Identifier"
+    ]
+  );
+}
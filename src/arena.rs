@@ -0,0 +1,104 @@
+use bumpalo::Bump;
+
+/// A bump arena for token values, so a token holds a `&V` into memory that
+/// is allocated in large blocks and freed all at once when the arena is
+/// dropped or reset. Readers are `'static` and can not borrow the arena, so
+/// values are moved into it as tokens are read.
+///
+/// Values are never dropped, so values owning heap memory, like `String`,
+/// leak it; copy text in with `alloc_str` instead.
+///
+/// ```
+/// use lexer::readers::{IdentifierReader, WhitespaceReader};
+/// use lexer::{Arena, ReadersBuilder, Token};
+///
+/// let arena = Arena::new();
+/// let readers = ReadersBuilder::<Token<String>, ()>::new()
+///   .add(WhitespaceReader::new())
+///   .add(IdentifierReader::new(|string, meta| Token::new(meta, string)))
+///   .build();
+/// let tokens: Vec<Token<&str>> = readers
+///   .read("a bc".chars())
+///   .map(|result| result.unwrap().map(|string| arena.alloc_str(&string)))
+///   .collect();
+///
+/// assert_eq!(*tokens[1].value(), "bc");
+/// ```
+#[derive(Debug, Default)]
+pub struct Arena {
+  bump: Bump,
+}
+
+impl Arena {
+  #[inline]
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// An arena with `bytes` allocated up front.
+  #[inline]
+  pub fn with_capacity(bytes: usize) -> Self {
+    Arena {
+      bump: Bump::with_capacity(bytes),
+    }
+  }
+
+  #[inline(always)]
+  pub fn alloc<T>(&self, value: T) -> &T {
+    self.bump.alloc(value)
+  }
+
+  #[inline(always)]
+  pub fn alloc_str(&self, string: &str) -> &str {
+    self.bump.alloc_str(string)
+  }
+
+  /// The bytes allocated in blocks so far, including unused space.
+  #[inline(always)]
+  pub fn allocated_bytes(&self) -> usize {
+    self.bump.allocated_bytes()
+  }
+
+  /// Frees every value at once, keeping the last block for reuse.
+  #[inline]
+  pub fn reset(&mut self) {
+    self.bump.reset();
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::super::{Token, TokenMeta};
+  use super::*;
+
+  #[derive(Debug, PartialEq)]
+  enum Value<'a> {
+    Number(u64),
+    Identifier(&'a str),
+  }
+
+  #[test]
+  fn test_arena() {
+    let mut arena = Arena::with_capacity(64);
+    let tokens: alloc::vec::Vec<Token<&Value>> = [("1", 0), ("ab", 2)]
+      .iter()
+      .map(|&(text, start)| {
+        let value = match text.parse() {
+          Ok(number) => Value::Number(number),
+          Err(_) => Value::Identifier(arena.alloc_str(text)),
+        };
+        Token::new(
+          TokenMeta::new(start, start + 1, 1, 1, 1, 1),
+          arena.alloc(value),
+        )
+      })
+      .collect();
+
+    assert_eq!(tokens[0].value(), &&Value::Number(1));
+    assert_eq!(tokens[1].value(), &&Value::Identifier("ab"));
+    assert!(arena.allocated_bytes() >= 64);
+
+    drop(tokens);
+    arena.reset();
+  }
+}
@@ -0,0 +1,54 @@
+use super::{AttemptResult, State};
+
+/// One reader trying to read at a position, as `Readers::with_observer`
+/// reports it. `end` is where the reader left its state, also when it
+/// returned `None` and the state was thrown away.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Attempt<'a> {
+  index: usize,
+  name: &'a str,
+  start: &'a State,
+  end: &'a State,
+  result: AttemptResult,
+}
+
+impl<'a> Attempt<'a> {
+  #[inline(always)]
+  pub(crate) fn new(
+    index: usize,
+    name: &'a str,
+    start: &'a State,
+    end: &'a State,
+    result: AttemptResult,
+  ) -> Self {
+    Attempt {
+      index,
+      name,
+      start,
+      end,
+      result,
+    }
+  }
+
+  /// The index of the reader in its `Readers`.
+  #[inline(always)]
+  pub fn index(&self) -> usize {
+    self.index
+  }
+  #[inline(always)]
+  pub fn name(&self) -> &'a str {
+    self.name
+  }
+  #[inline(always)]
+  pub fn start(&self) -> &'a State {
+    self.start
+  }
+  #[inline(always)]
+  pub fn end(&self) -> &'a State {
+    self.end
+  }
+  #[inline(always)]
+  pub fn result(&self) -> AttemptResult {
+    self.result
+  }
+}
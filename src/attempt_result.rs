@@ -0,0 +1,50 @@
+use core::fmt;
+
+use super::ReaderResult;
+
+/// Which `ReaderResult` variant a reader returned, without its tokens.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum AttemptResult {
+  Some,
+  /// with the number of tokens and errors
+  Many(usize),
+  Err,
+  Empty,
+  None,
+}
+
+impl AttemptResult {
+  #[inline]
+  pub fn as_str(&self) -> &'static str {
+    match *self {
+      AttemptResult::Some => "some",
+      AttemptResult::Many(_) => "many",
+      AttemptResult::Err => "err",
+      AttemptResult::Empty => "empty",
+      AttemptResult::None => "none",
+    }
+  }
+}
+
+impl<'a, T, E> From<&'a ReaderResult<T, E>> for AttemptResult {
+  #[inline]
+  fn from(result: &'a ReaderResult<T, E>) -> Self {
+    match *result {
+      ReaderResult::Some(_) => AttemptResult::Some,
+      ReaderResult::Many(ref results) => AttemptResult::Many(results.len()),
+      ReaderResult::Err(_) => AttemptResult::Err,
+      ReaderResult::Empty => AttemptResult::Empty,
+      ReaderResult::None => AttemptResult::None,
+    }
+  }
+}
+
+impl fmt::Display for AttemptResult {
+  #[inline]
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match *self {
+      AttemptResult::Many(count) => write!(f, "many({})", count),
+      _ => f.write_str(self.as_str()),
+    }
+  }
+}
@@ -0,0 +1,504 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+
+use super::{Input, Reader, ReaderResult, Readers, State, TokenMeta};
+
+/// Reads an `O` from `input`, advancing `state` past what it consumed. A
+/// parser returning `None` may leave `state` anywhere, combinators that try
+/// something else afterwards restore it from a clone.
+pub trait Parser<O> {
+  fn parse(&self, input: &mut dyn Input, state: &mut State) -> Option<O>;
+}
+
+impl<O, F> Parser<O> for F
+where
+  F: Fn(&mut dyn Input, &mut State) -> Option<O>,
+{
+  #[inline(always)]
+  fn parse(&self, input: &mut dyn Input, state: &mut State) -> Option<O> {
+    self(input, state)
+  }
+}
+
+#[inline]
+fn attempt<O, P>(parser: &P, input: &mut dyn Input, state: &mut State) -> Option<O>
+where
+  P: Parser<O> + ?Sized,
+{
+  let mut next = state.clone();
+
+  match parser.parse(input, &mut next) {
+    Some(output) => {
+      state.clone_from(&next);
+      Some(output)
+    }
+    None => None,
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Satisfy<F>(F);
+
+#[inline]
+pub fn satisfy<F>(f: F) -> Satisfy<F>
+where
+  F: Fn(char) -> bool,
+{
+  Satisfy(f)
+}
+
+impl<F> Parser<char> for Satisfy<F>
+where
+  F: Fn(char) -> bool,
+{
+  #[inline]
+  fn parse(&self, input: &mut dyn Input, state: &mut State) -> Option<char> {
+    match input.peek(state, 0) {
+      Some(ch) if (self.0)(ch) => input.read(state),
+      _ => None,
+    }
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Char(char);
+
+#[inline]
+pub fn char(ch: char) -> Char {
+  Char(ch)
+}
+
+impl Parser<char> for Char {
+  #[inline]
+  fn parse(&self, input: &mut dyn Input, state: &mut State) -> Option<char> {
+    match input.peek(state, 0) {
+      Some(ch) if ch == self.0 => input.read(state),
+      _ => None,
+    }
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct OneOf<'a>(&'a str, bool);
+
+#[inline]
+pub fn one_of(chars: &str) -> OneOf<'_> {
+  OneOf(chars, true)
+}
+
+#[inline]
+pub fn none_of(chars: &str) -> OneOf<'_> {
+  OneOf(chars, false)
+}
+
+impl Parser<char> for OneOf<'_> {
+  #[inline]
+  fn parse(&self, input: &mut dyn Input, state: &mut State) -> Option<char> {
+    match input.peek(state, 0) {
+      Some(ch) if self.0.contains(ch) == self.1 => input.read(state),
+      _ => None,
+    }
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Tag<'a>(&'a str);
+
+#[inline]
+pub fn tag(tag: &str) -> Tag<'_> {
+  Tag(tag)
+}
+
+impl<'a> Parser<&'a str> for Tag<'a> {
+  #[inline]
+  fn parse(&self, input: &mut dyn Input, state: &mut State) -> Option<&'a str> {
+    for (offset, ch) in self.0.chars().enumerate() {
+      if input.peek(state, offset) != Some(ch) {
+        return None;
+      }
+    }
+    for _ in self.0.chars() {
+      input.read(state);
+    }
+    Some(self.0)
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Many<P, O> {
+  parser: P,
+  min: usize,
+  _output: PhantomData<fn() -> O>,
+}
+
+#[inline]
+pub fn many0<P, O>(parser: P) -> Many<P, O>
+where
+  P: Parser<O>,
+{
+  Many {
+    parser,
+    min: 0,
+    _output: PhantomData,
+  }
+}
+
+#[inline]
+pub fn many1<P, O>(parser: P) -> Many<P, O>
+where
+  P: Parser<O>,
+{
+  Many {
+    parser,
+    min: 1,
+    _output: PhantomData,
+  }
+}
+
+impl<P, O> Parser<Vec<O>> for Many<P, O>
+where
+  P: Parser<O>,
+{
+  #[inline]
+  fn parse(&self, input: &mut dyn Input, state: &mut State) -> Option<Vec<O>> {
+    let mut outputs = Vec::new();
+
+    loop {
+      let index = state.index();
+
+      match attempt(&self.parser, input, state) {
+        Some(output) if state.index() > index => outputs.push(output),
+        _ => break,
+      }
+    }
+
+    if outputs.len() < self.min {
+      None
+    } else {
+      Some(outputs)
+    }
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Opt<P, O> {
+  parser: P,
+  _output: PhantomData<fn() -> O>,
+}
+
+#[inline]
+pub fn opt<P, O>(parser: P) -> Opt<P, O>
+where
+  P: Parser<O>,
+{
+  Opt {
+    parser,
+    _output: PhantomData,
+  }
+}
+
+impl<P, O> Parser<Option<O>> for Opt<P, O>
+where
+  P: Parser<O>,
+{
+  #[inline]
+  fn parse(&self, input: &mut dyn Input, state: &mut State) -> Option<Option<O>> {
+    Some(attempt(&self.parser, input, state))
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Recognize<P, O> {
+  parser: P,
+  _output: PhantomData<fn() -> O>,
+}
+
+/// Returns the chars `parser` consumed instead of its output.
+#[inline]
+pub fn recognize<P, O>(parser: P) -> Recognize<P, O>
+where
+  P: Parser<O>,
+{
+  Recognize {
+    parser,
+    _output: PhantomData,
+  }
+}
+
+impl<P, O> Parser<String> for Recognize<P, O>
+where
+  P: Parser<O>,
+{
+  #[inline]
+  fn parse(&self, input: &mut dyn Input, state: &mut State) -> Option<String> {
+    let start = state.clone();
+
+    self.parser.parse(input, state)?;
+
+    Some(
+      (0..(state.index() - start.index()))
+        .filter_map(|offset| input.peek(&start, offset))
+        .collect(),
+    )
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Map<P, F, O> {
+  parser: P,
+  f: F,
+  _output: PhantomData<fn() -> O>,
+}
+
+#[inline]
+pub fn map<P, F, O, U>(parser: P, f: F) -> Map<P, F, O>
+where
+  P: Parser<O>,
+  F: Fn(O) -> U,
+{
+  Map {
+    parser,
+    f,
+    _output: PhantomData,
+  }
+}
+
+impl<P, F, O, U> Parser<U> for Map<P, F, O>
+where
+  P: Parser<O>,
+  F: Fn(O) -> U,
+{
+  #[inline]
+  fn parse(&self, input: &mut dyn Input, state: &mut State) -> Option<U> {
+    self.parser.parse(input, state).map(&self.f)
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Seq<P>(P);
+
+/// Runs a tuple of parsers one after the other, returning a tuple of their
+/// outputs.
+#[inline]
+pub fn seq<P>(parsers: P) -> Seq<P> {
+  Seq(parsers)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Alt<P>(P);
+
+/// Tries a tuple of parsers in order from the same state, returning the
+/// output of the first that succeeds.
+#[inline]
+pub fn alt<P>(parsers: P) -> Alt<P> {
+  Alt(parsers)
+}
+
+macro_rules! impl_tuple_parsers {
+  ($($P:ident $O:ident $index:tt),+) => {
+    impl<$($P, $O),+> Parser<($($O,)+)> for Seq<($($P,)+)>
+    where
+      $($P: Parser<$O>),+
+    {
+      #[inline]
+      fn parse(&self, input: &mut dyn Input, state: &mut State) -> Option<($($O,)+)> {
+        Some(($((self.0).$index.parse(input, state)?,)+))
+      }
+    }
+
+    impl<O, $($P),+> Parser<O> for Alt<($($P,)+)>
+    where
+      $($P: Parser<O>),+
+    {
+      #[inline]
+      fn parse(&self, input: &mut dyn Input, state: &mut State) -> Option<O> {
+        $(
+          if let Some(output) = attempt(&(self.0).$index, input, state) {
+            return Some(output);
+          }
+        )+
+        None
+      }
+    }
+  };
+}
+
+impl_tuple_parsers!(P0 O0 0);
+impl_tuple_parsers!(P0 O0 0, P1 O1 1);
+impl_tuple_parsers!(P0 O0 0, P1 O1 1, P2 O2 2);
+impl_tuple_parsers!(P0 O0 0, P1 O1 1, P2 O2 2, P3 O3 3);
+impl_tuple_parsers!(P0 O0 0, P1 O1 1, P2 O2 2, P3 O3 3, P4 O4 4);
+impl_tuple_parsers!(P0 O0 0, P1 O1 1, P2 O2 2, P3 O3 3, P4 O4 4, P5 O5 5);
+impl_tuple_parsers!(P0 O0 0, P1 O1 1, P2 O2 2, P3 O3 3, P4 O4 4, P5 O5 5, P6 O6 6);
+impl_tuple_parsers!(P0 O0 0, P1 O1 1, P2 O2 2, P3 O3 3, P4 O4 4, P5 O5 5, P6 O6 6, P7 O7 7);
+
+/// Adapts a parser into a `Reader` that builds a token from its output and
+/// the `TokenMeta` of what it consumed. Parsers that consume nothing do not
+/// produce a token.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ParserReader<P, F, O> {
+  parser: P,
+  f: F,
+  _output: PhantomData<fn() -> O>,
+}
+
+#[inline]
+pub fn token<P, F, O, T>(parser: P, f: F) -> ParserReader<P, F, O>
+where
+  P: Parser<O>,
+  F: Fn(O, TokenMeta) -> T,
+{
+  ParserReader {
+    parser,
+    f,
+    _output: PhantomData,
+  }
+}
+
+impl<P, F, O, T, E> Reader<T, E> for ParserReader<P, F, O>
+where
+  P: Parser<O>,
+  F: Fn(O, TokenMeta) -> T,
+{
+  #[inline]
+  fn read(
+    &self,
+    _: &Readers<T, E>,
+    input: &mut dyn Input,
+    current: &State,
+    next: &mut State,
+  ) -> ReaderResult<T, E> {
+    match self.parser.parse(input, next) {
+      Some(output) if next.index() > current.index() => {
+        ReaderResult::Some((self.f)(output, TokenMeta::new_state_meta(current, next)))
+      }
+      _ => ReaderResult::None,
+    }
+  }
+}
+
+/// Adapts a parser into a `Reader` that skips what it consumes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TriviaReader<P, O> {
+  parser: P,
+  _output: PhantomData<fn() -> O>,
+}
+
+#[inline]
+pub fn trivia<P, O>(parser: P) -> TriviaReader<P, O>
+where
+  P: Parser<O>,
+{
+  TriviaReader {
+    parser,
+    _output: PhantomData,
+  }
+}
+
+impl<P, O, T, E> Reader<T, E> for TriviaReader<P, O>
+where
+  P: Parser<O>,
+{
+  #[inline]
+  fn read(
+    &self,
+    _: &Readers<T, E>,
+    input: &mut dyn Input,
+    current: &State,
+    next: &mut State,
+  ) -> ReaderResult<T, E> {
+    match self.parser.parse(input, next) {
+      Some(_) if next.index() > current.index() => ReaderResult::Empty,
+      _ => ReaderResult::None,
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::super::{ReadersBuilder, Token};
+  use super::*;
+  use alloc::vec;
+  use peek_nth::IteratorExt;
+
+  fn parse<P, O>(parser: P, string: &str) -> Option<(O, usize)>
+  where
+    P: Parser<O>,
+  {
+    let mut state = State::new();
+    let output = parser.parse(&mut string.chars().peekable_nth(), &mut state)?;
+    Some((output, state.index()))
+  }
+
+  #[test]
+  fn test_primitives() {
+    assert_eq!(parse(char('a'), "ab"), Some(('a', 1)));
+    assert_eq!(parse(char('a'), "ba"), None);
+    assert_eq!(parse(one_of("+-"), "-1"), Some(('-', 1)));
+    assert_eq!(parse(none_of("+-"), "-1"), None);
+    assert_eq!(parse(tag("let"), "let x"), Some(("let", 3)));
+    assert_eq!(parse(tag("let"), "le"), None);
+  }
+
+  #[test]
+  fn test_repetition() {
+    let digits = many1(satisfy(|ch: char| ch.is_ascii_digit()));
+
+    assert_eq!(parse(digits, "123a"), Some((vec!['1', '2', '3'], 3)));
+    assert_eq!(parse(digits, "a"), None);
+    assert_eq!(parse(many0(char('x')), "a"), Some((vec![], 0)));
+    assert_eq!(
+      parse(many0(opt(char('x'))), "xa"),
+      Some((vec![Some('x')], 1))
+    );
+  }
+
+  #[test]
+  fn test_backtracking() {
+    let parser = alt((
+      recognize(seq((char('a'), char('b')))),
+      recognize(seq((char('a'), char('c')))),
+    ));
+
+    assert_eq!(parse(parser, "acd"), Some(("ac".into(), 2)));
+    assert_eq!(parse(parser, "ad"), None);
+
+    let number = recognize(seq((
+      many1(satisfy(|ch: char| ch.is_ascii_digit())),
+      opt(seq((
+        char('.'),
+        many1(satisfy(|ch: char| ch.is_ascii_digit())),
+      ))),
+    )));
+
+    assert_eq!(parse(number.clone(), "1.5"), Some(("1.5".into(), 3)));
+    assert_eq!(parse(number.clone(), "1.x"), Some(("1".into(), 1)));
+    assert_eq!(parse(map(number, |s: String| s.len()), "12"), Some((2, 2)));
+  }
+
+  #[test]
+  fn test_readers() {
+    let readers = ReadersBuilder::<Token<String>, ()>::new()
+      .add(trivia(many1(one_of(" \n"))))
+      .add(token(
+        recognize(seq((
+          satisfy(char::is_alphabetic),
+          many0(satisfy(char::is_alphanumeric)),
+        ))),
+        |string, meta| Token::new(meta, string),
+      ))
+      .build();
+    let tokens: Vec<Token<String>> = readers
+      .read("ab1\n c".chars())
+      .map(Result::unwrap)
+      .collect();
+
+    assert_eq!(tokens.len(), 2);
+    assert_eq!(tokens[0].value(), "ab1");
+    assert_eq!(tokens[0].meta().col_end(), 4);
+    assert_eq!(tokens[1].value(), "c");
+    assert_eq!(tokens[1].meta().line_start(), 2);
+    assert_eq!(tokens[1].meta().col_start(), 2);
+  }
+}
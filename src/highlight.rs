@@ -0,0 +1,350 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::Write;
+
+use super::{Readers, Token};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum HighlightFormat {
+  /// styles are SGR parameters, `"1;32"` becomes `"\x1b[1;32m"`
+  Ansi,
+  /// styles are CSS classes of a `<span>`
+  Html,
+}
+
+/// Colours `source` with the tokens `readers` produce. Text between tokens,
+/// like skipped whitespace and comments, is copied verbatim, and so is the
+/// text of tokens that fail to lex, after which highlighting goes on.
+/// `children` gives the tokens nested in a value, which are styled on their
+/// own, with unstyled children taking their parent's style.
+#[inline]
+pub fn highlight<V, E, C, F, S>(
+  source: &str,
+  readers: &Readers<Token<V>, E>,
+  format: HighlightFormat,
+  children: C,
+  mut style: F,
+) -> String
+where
+  C: for<'a> Fn(&'a V) -> &'a [Token<V>],
+  F: FnMut(&V) -> Option<S>,
+  S: AsRef<str>,
+{
+  let offsets: Vec<usize> = source
+    .char_indices()
+    .map(|(offset, _)| offset)
+    .chain(Some(source.len()))
+    .collect();
+  let mut highlighter = Highlighter {
+    source,
+    offsets: &offsets,
+    format,
+    output: String::with_capacity(source.len()),
+    offset: 0,
+  };
+
+  for token in readers.read(source.chars()).filter_map(Result::ok) {
+    let (start, _) = highlighter.span(&token);
+    let offset = highlighter.offset;
+    highlighter.write(offset, start, None);
+    highlighter.write_token(&token, None, &children, &mut style);
+  }
+
+  let (offset, end) = (highlighter.offset, source.len());
+  highlighter.write(offset, end, None);
+  highlighter.output
+}
+
+struct Highlighter<'s> {
+  source: &'s str,
+  offsets: &'s [usize],
+  format: HighlightFormat,
+  output: String,
+  offset: usize,
+}
+
+impl<'s> Highlighter<'s> {
+  #[inline]
+  fn span<V>(&self, token: &Token<V>) -> (usize, usize) {
+    let start = self.offsets[token.meta().index_start() as usize].max(self.offset);
+    let end = self.offsets[token.meta().index_end() as usize].max(start);
+    (start, end)
+  }
+
+  #[inline]
+  fn write_token<V, C, F, S>(
+    &mut self,
+    token: &Token<V>,
+    parent: Option<&str>,
+    children: &C,
+    style: &mut F,
+  ) where
+    C: for<'a> Fn(&'a V) -> &'a [Token<V>],
+    F: FnMut(&V) -> Option<S>,
+    S: AsRef<str>,
+  {
+    let (start, end) = self.span(token);
+    let own = style(token.value());
+    let token_style = own.as_ref().map(AsRef::as_ref).or(parent);
+
+    self.offset = start;
+
+    for child in children(token.value()) {
+      let (child_start, child_end) = self.span(child);
+
+      if child_end > end {
+        break;
+      }
+      let offset = self.offset;
+      self.write(offset, child_start, token_style);
+      self.write_token(child, token_style, children, style);
+    }
+
+    let offset = self.offset.min(end);
+    self.write(offset, end, token_style);
+  }
+
+  #[inline]
+  fn write(&mut self, start: usize, end: usize, style: Option<&str>) {
+    if start < end {
+      let text = &self.source[start..end];
+
+      match style {
+        Some(style) => write_styled(&mut self.output, self.format, style, text),
+        None => write_text(&mut self.output, self.format, text),
+      }
+    }
+    self.offset = self.offset.max(end);
+  }
+}
+
+#[inline]
+fn write_styled(output: &mut String, format: HighlightFormat, style: &str, text: &str) {
+  if text.is_empty() {
+    return;
+  }
+
+  match format {
+    HighlightFormat::Ansi => {
+      let _ = write!(output, "\x1b[{}m{}\x1b[0m", style, text);
+    }
+    HighlightFormat::Html => {
+      output.push_str("<span class=\"");
+      write_html_escaped(output, style);
+      output.push_str("\">");
+      write_html_escaped(output, text);
+      output.push_str("</span>");
+    }
+  }
+}
+
+#[inline]
+fn write_text(output: &mut String, format: HighlightFormat, text: &str) {
+  match format {
+    HighlightFormat::Ansi => output.push_str(text),
+    HighlightFormat::Html => write_html_escaped(output, text),
+  }
+}
+
+#[inline]
+fn write_html_escaped(output: &mut String, text: &str) {
+  for ch in text.chars() {
+    match ch {
+      '&' => output.push_str("&amp;"),
+      '<' => output.push_str("&lt;"),
+      '>' => output.push_str("&gt;"),
+      '"' => output.push_str("&quot;"),
+      '\'' => output.push_str("&#39;"),
+      ch => output.push(ch),
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::super::{read_many, Input, Reader, ReaderResult, ReadersBuilder, State, TokenMeta};
+  use super::*;
+
+  #[derive(Debug)]
+  enum Value {
+    Word(bool),
+    Group(Vec<Token<Value>>),
+  }
+
+  fn children(value: &Value) -> &[Token<Value>] {
+    match value {
+      Value::Group(tokens) => tokens,
+      Value::Word(_) => &[],
+    }
+  }
+
+  struct GroupReader;
+
+  impl Reader<Token<Value>, ()> for GroupReader {
+    fn read(
+      &self,
+      readers: &Readers<Token<Value>, ()>,
+      input: &mut dyn Input,
+      current: &State,
+      next: &mut State,
+    ) -> ReaderResult<Token<Value>, ()> {
+      if input.read(next) != Some('(') {
+        return ReaderResult::None;
+      }
+      let mut tokens = Vec::new();
+
+      while input.peek(next, 0) != Some(')') {
+        match read_many(readers, input, next) {
+          Some(results) => tokens.extend(results.into_iter().filter_map(Result::ok)),
+          None => return ReaderResult::None,
+        }
+      }
+      input.read(next);
+
+      ReaderResult::Some(Token::new(
+        TokenMeta::new_state_meta(current, next),
+        Value::Group(tokens),
+      ))
+    }
+  }
+
+  struct WordReader;
+
+  impl Reader<Token<Value>, ()> for WordReader {
+    fn read(
+      &self,
+      _: &Readers<Token<Value>, ()>,
+      input: &mut dyn Input,
+      current: &State,
+      next: &mut State,
+    ) -> ReaderResult<Token<Value>, ()> {
+      let mut is_number = true;
+      let mut is_error = false;
+
+      while let Some(ch) = input.peek(next, 0) {
+        if ch.is_whitespace() || ch == '(' || ch == ')' {
+          break;
+        }
+        is_number &= ch.is_numeric();
+        is_error |= ch == '!';
+        input.read(next);
+      }
+
+      if current.index() == next.index() {
+        match input.read(next) {
+          Some(_) => ReaderResult::Empty,
+          None => ReaderResult::None,
+        }
+      } else if is_error {
+        ReaderResult::Err(())
+      } else {
+        ReaderResult::Some(Token::new(
+          TokenMeta::new_state_meta(current, next),
+          Value::Word(is_number),
+        ))
+      }
+    }
+  }
+
+  fn readers() -> Readers<Token<Value>, ()> {
+    ReadersBuilder::new()
+      .add(GroupReader)
+      .add(WordReader)
+      .build()
+  }
+
+  fn html_style(value: &Value) -> Option<&'static str> {
+    match value {
+      Value::Word(true) => Some("number"),
+      Value::Word(false) => Some("word"),
+      Value::Group(_) => Some("group"),
+    }
+  }
+
+  #[test]
+  fn test_highlight_ansi() {
+    let output = highlight(
+      "a  12\n<é>",
+      &readers(),
+      HighlightFormat::Ansi,
+      children,
+      |value| match value {
+        Value::Word(true) => Some("1;32"),
+        _ => None,
+      },
+    );
+
+    assert_eq!(output, "a  \x1b[1;32m12\x1b[0m\n<é>");
+  }
+
+  #[test]
+  fn test_highlight_html() {
+    let output = highlight(
+      " <é> 1 ",
+      &readers(),
+      HighlightFormat::Html,
+      children,
+      html_style,
+    );
+
+    assert_eq!(
+      output,
+      " <span class=\"word\">&lt;é&gt;</span> <span class=\"number\">1</span> "
+    );
+  }
+
+  #[test]
+  fn test_highlight_after_error() {
+    let output = highlight(
+      "a b! 1",
+      &readers(),
+      HighlightFormat::Html,
+      children,
+      html_style,
+    );
+
+    assert_eq!(
+      output,
+      "<span class=\"word\">a</span> b! <span class=\"number\">1</span>"
+    );
+  }
+
+  #[test]
+  fn test_highlight_nested() {
+    let output = highlight(
+      "(a (1))",
+      &readers(),
+      HighlightFormat::Html,
+      children,
+      html_style,
+    );
+
+    assert_eq!(
+      output,
+      "<span class=\"group\">(</span><span class=\"word\">a</span>\
+       <span class=\"group\"> </span><span class=\"group\">(</span>\
+       <span class=\"number\">1</span>\
+       <span class=\"group\">)</span><span class=\"group\">)</span>"
+    );
+  }
+
+  #[test]
+  fn test_highlight_nested_inherits_style() {
+    let output = highlight(
+      "(a 1)",
+      &readers(),
+      HighlightFormat::Ansi,
+      children,
+      |value| match value {
+        Value::Group(_) => Some("1"),
+        Value::Word(true) => Some("32"),
+        Value::Word(false) => None,
+      },
+    );
+
+    assert_eq!(
+      output,
+      "\x1b[1m(\x1b[0m\x1b[1ma\x1b[0m\x1b[1m \x1b[0m\x1b[32m1\x1b[0m\x1b[1m)\x1b[0m"
+    );
+  }
+}
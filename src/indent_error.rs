@@ -0,0 +1,27 @@
+use core::fmt;
+
+/// Errors `IndentIter` reports about a line's leading whitespace.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum IndentError {
+  /// a dedent to a width no enclosing line was indented to
+  InconsistentDedent,
+  /// tabs and spaces mixed on a line, or used on different lines
+  MixedIndentation,
+}
+
+impl IndentError {
+  #[inline]
+  pub fn as_str(&self) -> &'static str {
+    match *self {
+      IndentError::InconsistentDedent => "inconsistent dedent",
+      IndentError::MixedIndentation => "mixed indentation",
+    }
+  }
+}
+
+impl fmt::Display for IndentError {
+  #[inline]
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    f.write_str(self.as_str())
+  }
+}
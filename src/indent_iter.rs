@@ -0,0 +1,385 @@
+use alloc::collections::VecDeque;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use peek_nth::IteratorExt;
+
+use super::read::read_step;
+use super::{IndentError, Indentation, Input, ReaderResult, State, TokenIter, TokenMeta};
+
+/// The leading whitespace of a line, applied once the line turns out to hold
+/// a token.
+struct LineStart {
+  start: State,
+  end: State,
+  spaces: bool,
+  tabs: bool,
+}
+
+/// Reads tokens like `TokenIter`, inserting zero width `Indentation` tokens
+/// wherever a line's leading whitespace is wider or narrower than that of
+/// the lines before it. The width is the distance in `State::col`, so tabs
+/// count as the state's `ColumnMode` says. Line breaks are handled here,
+/// and lines holding nothing but whitespace and skipped input are ignored.
+pub struct IndentIter<'a, T, E, I, F, G>
+where
+  T: 'a,
+  E: 'a,
+  I: 'a + IteratorExt<Item = char>,
+{
+  iter: TokenIter<'a, T, E, I>,
+  levels: Vec<u64>,
+  indent_char: Option<char>,
+  line_start: Option<LineStart>,
+  is_line_start: bool,
+  is_done: bool,
+  queue: VecDeque<Result<T, E>>,
+  to_token: F,
+  to_error: G,
+}
+
+impl<'a, T, E, I, F, G> IndentIter<'a, T, E, I, F, G>
+where
+  T: 'a,
+  E: 'a,
+  I: 'a + IteratorExt<Item = char>,
+  F: Fn(Indentation, TokenMeta) -> T,
+  G: Fn(IndentError, TokenMeta) -> E,
+{
+  #[inline]
+  pub fn new(iter: TokenIter<'a, T, E, I>, to_token: F, to_error: G) -> Self {
+    IndentIter {
+      iter,
+      levels: vec![0],
+      indent_char: None,
+      line_start: None,
+      is_line_start: true,
+      is_done: false,
+      queue: VecDeque::new(),
+      to_token,
+      to_error,
+    }
+  }
+
+  #[inline]
+  fn read_line_start(&mut self) {
+    let state = &mut self.iter.state;
+    let input: &mut dyn Input = &mut self.iter.input;
+    let start = state.clone();
+    let mut spaces = false;
+    let mut tabs = false;
+
+    while let Some(ch) = input.peek(state, 0) {
+      match ch {
+        ' ' => spaces = true,
+        '\t' => tabs = true,
+        _ => break,
+      }
+      input.read(state);
+    }
+
+    self.line_start = Some(LineStart {
+      start,
+      end: state.clone(),
+      spaces,
+      tabs,
+    });
+  }
+
+  /// Reads any trailing spaces and tabs plus the line break after them, if
+  /// that is all that is left of the line. A `'\r'` right before a `'\n'`
+  /// is part of the line break whatever the `NewlineMode`.
+  #[inline]
+  fn skip_line_break(&mut self) -> bool {
+    let state = &mut self.iter.state;
+    let input: &mut dyn Input = &mut self.iter.input;
+    let mut offset = 0;
+
+    while let Some(' ') | Some('\t') = input.peek(state, offset) {
+      offset += 1;
+    }
+    if input.peek(state, offset) == Some('\r') && input.peek(state, offset + 1) == Some('\n') {
+      offset += 1;
+    }
+
+    match input.peek(state, offset) {
+      Some(ch) if state.is_line_break(ch) => {
+        input.read_offset(state, offset + 1);
+        input.skip_crlf(state, ch);
+        self.line_start = None;
+        self.is_line_start = true;
+        true
+      }
+      _ => false,
+    }
+  }
+
+  #[inline]
+  fn indent(&mut self, line_start: LineStart) {
+    let width = line_start.end.col() - line_start.start.col();
+    let indent_char = if line_start.tabs { '\t' } else { ' ' };
+
+    if (line_start.spaces && line_start.tabs)
+      || (width != 0 && self.indent_char.is_some_and(|ch| ch != indent_char))
+    {
+      self.queue.push_back(Err((self.to_error)(
+        IndentError::MixedIndentation,
+        TokenMeta::new_state_meta(&line_start.start, &line_start.end),
+      )));
+      return;
+    }
+    if width != 0 && self.indent_char.is_none() {
+      self.indent_char = Some(indent_char);
+    }
+
+    let meta = TokenMeta::new_point_meta(&line_start.end);
+
+    if width > self.level() {
+      self.levels.push(width);
+      self
+        .queue
+        .push_back(Ok((self.to_token)(Indentation::Indent, meta)));
+    } else {
+      while width < self.level() {
+        self.levels.pop();
+        self
+          .queue
+          .push_back(Ok((self.to_token)(Indentation::Dedent, meta.clone())));
+      }
+      if width != self.level() {
+        self.levels.push(width);
+        self.queue.push_back(Err((self.to_error)(
+          IndentError::InconsistentDedent,
+          TokenMeta::new_state_meta(&line_start.start, &line_start.end),
+        )));
+      }
+    }
+  }
+
+  #[inline]
+  fn level(&self) -> u64 {
+    self.levels.last().cloned().unwrap_or(0)
+  }
+}
+
+impl<'a, T, E, I, F, G> Iterator for IndentIter<'a, T, E, I, F, G>
+where
+  T: 'a,
+  E: 'a,
+  I: 'a + IteratorExt<Item = char>,
+  F: Fn(Indentation, TokenMeta) -> T,
+  G: Fn(IndentError, TokenMeta) -> E,
+{
+  type Item = Result<T, E>;
+
+  #[inline]
+  fn next(&mut self) -> Option<Self::Item> {
+    loop {
+      if let Some(item) = self.queue.pop_front() {
+        return Some(item);
+      }
+      if self.is_done {
+        return None;
+      }
+      if self.is_line_start {
+        self.is_line_start = false;
+        self.read_line_start();
+      }
+      if self.skip_line_break() {
+        continue;
+      }
+
+      match read_step(
+        self.iter.readers,
+        &mut self.iter.input,
+        &mut self.iter.state,
+      ) {
+        Some((_, ReaderResult::Empty)) | Some((_, ReaderResult::None)) => (),
+        Some((_, ReaderResult::Many(ref results))) if results.is_empty() => (),
+        Some((_, result)) => {
+          if let Some(line_start) = self.line_start.take() {
+            self.indent(line_start);
+          }
+
+          match result {
+            ReaderResult::Some(token) => self.queue.push_back(Ok(token)),
+            ReaderResult::Err(error) => self.queue.push_back(Err(error)),
+            ReaderResult::Many(results) => self.queue.extend(results),
+            ReaderResult::Empty | ReaderResult::None => (),
+          }
+        }
+        None => {
+          let meta = TokenMeta::new_point_meta(&self.iter.state);
+
+          while self.levels.len() > 1 {
+            self.levels.pop();
+            self
+              .queue
+              .push_back(Ok((self.to_token)(Indentation::Dedent, meta.clone())));
+          }
+          self.is_done = true;
+        }
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use alloc::string::String;
+  use alloc::vec::Vec;
+
+  use super::super::readers::{LineCommentReader, WhitespaceReader};
+  use super::super::{char_class, ColumnMode, NewlineMode, Readers, ReadersBuilder};
+  use super::*;
+
+  type Item = Result<(Value, u64, u64), (IndentError, u64, u64)>;
+
+  #[derive(Debug, PartialEq)]
+  enum Value {
+    Ident(String),
+    Indentation(Indentation),
+  }
+
+  fn readers() -> Readers<(Value, u64, u64), (IndentError, u64, u64)> {
+    ReadersBuilder::new()
+      .add(WhitespaceReader::new())
+      .add(LineCommentReader::new("#"))
+      .add(char_class(
+        |ch| ch.is_alphanumeric() || ch == ':',
+        |string, meta| (Value::Ident(string), meta.row_start(), meta.col_start()),
+      ))
+      .build()
+  }
+
+  fn lex(string: &str, state: State) -> Vec<Item> {
+    let readers = readers();
+
+    IndentIter::new(
+      TokenIter::new_with_state(&readers, string.chars(), state),
+      |indentation, meta| {
+        assert!(meta.is_empty());
+        (
+          Value::Indentation(indentation),
+          meta.row_start(),
+          meta.col_start(),
+        )
+      },
+      |error, meta| (error, meta.row_start(), meta.col_start()),
+    )
+    .collect()
+  }
+
+  fn ident(string: &str, row: u64, col: u64) -> Item {
+    Ok((Value::Ident(string.into()), row, col))
+  }
+
+  fn indentation(indentation: Indentation, row: u64, col: u64) -> Item {
+    Ok((Value::Indentation(indentation), row, col))
+  }
+
+  #[test]
+  fn test_indent_dedent() {
+    assert_eq!(
+      lex(
+        "if:\n  a\n\n  # comment\n      \n  while:\n    b\nc \n",
+        State::new()
+      ),
+      [
+        ident("if:", 1, 1),
+        indentation(Indentation::Indent, 2, 3),
+        ident("a", 2, 3),
+        ident("while:", 6, 3),
+        indentation(Indentation::Indent, 7, 5),
+        ident("b", 7, 5),
+        indentation(Indentation::Dedent, 8, 1),
+        indentation(Indentation::Dedent, 8, 1),
+        ident("c", 8, 1),
+      ]
+    );
+  }
+
+  #[test]
+  fn test_dedent_at_end() {
+    assert_eq!(
+      lex("a\n  b\n    c", State::new()),
+      [
+        ident("a", 1, 1),
+        indentation(Indentation::Indent, 2, 3),
+        ident("b", 2, 3),
+        indentation(Indentation::Indent, 3, 5),
+        ident("c", 3, 5),
+        indentation(Indentation::Dedent, 3, 6),
+        indentation(Indentation::Dedent, 3, 6),
+      ]
+    );
+  }
+
+  #[test]
+  fn test_crlf() {
+    let expected = [
+      ident("a:", 1, 1),
+      indentation(Indentation::Indent, 2, 3),
+      ident("b", 2, 3),
+      indentation(Indentation::Dedent, 3, 1),
+      ident("c", 3, 1),
+    ];
+
+    assert_eq!(lex("a:\r\n  b\r\nc", State::new()), expected);
+    assert_eq!(
+      lex(
+        "a:\r\n  b\r\nc",
+        State::new().with_newline_mode(NewlineMode::Crlf)
+      ),
+      expected
+    );
+  }
+
+  #[test]
+  fn test_tab_width() {
+    assert_eq!(
+      lex(
+        "a\n\tb\n\t\tc",
+        State::new().with_column_mode(ColumnMode::Visual(4))
+      ),
+      [
+        ident("a", 1, 1),
+        indentation(Indentation::Indent, 2, 5),
+        ident("b", 2, 5),
+        indentation(Indentation::Indent, 3, 9),
+        ident("c", 3, 9),
+        indentation(Indentation::Dedent, 3, 10),
+        indentation(Indentation::Dedent, 3, 10),
+      ]
+    );
+  }
+
+  #[test]
+  fn test_errors() {
+    assert_eq!(
+      lex("a\n    b\n  c", State::new()),
+      [
+        ident("a", 1, 1),
+        indentation(Indentation::Indent, 2, 5),
+        ident("b", 2, 5),
+        indentation(Indentation::Dedent, 3, 3),
+        Err((IndentError::InconsistentDedent, 3, 1)),
+        ident("c", 3, 3),
+        indentation(Indentation::Dedent, 3, 4),
+      ]
+    );
+    assert_eq!(
+      lex("a\n \tb\n  c\n\td", State::new()),
+      [
+        ident("a", 1, 1),
+        Err((IndentError::MixedIndentation, 2, 1)),
+        ident("b", 2, 3),
+        indentation(Indentation::Indent, 3, 3),
+        ident("c", 3, 3),
+        Err((IndentError::MixedIndentation, 4, 1)),
+        ident("d", 4, 2),
+        indentation(Indentation::Dedent, 4, 3),
+      ]
+    );
+  }
+}
@@ -0,0 +1,7 @@
+/// The synthetic tokens `IndentIter` inserts when a line is indented
+/// further than, or less than, the lines before it.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Indentation {
+  Indent,
+  Dedent,
+}
@@ -12,7 +12,7 @@ pub trait Input {
   fn read(&mut self, state: &mut State) -> Option<char> {
     match self.peek(state, 0) {
       Some(ch) => {
-        state.read(ch == '\n');
+        state.read(ch);
         Some(ch)
       }
       None => None,
@@ -38,7 +38,8 @@ pub trait Input {
   fn skip_line(&mut self, state: &mut State) {
     if !self.is_done(state) {
       while let Some(ch) = self.read(state) {
-        if ch == '\n' {
+        if state.is_line_break(ch) {
+          self.skip_crlf(state, ch);
           break;
         }
       }
@@ -54,7 +55,7 @@ pub trait Input {
       let offset = state.index();
 
       while let Some(ch) = self.peek(state, index) {
-        if ch != '\n' {
+        if !state.is_line_break(ch) {
           index += 1;
           string.push(ch);
         } else {
@@ -74,9 +75,10 @@ pub trait Input {
       let offset = state.index();
 
       while let Some(ch) = self.read(state) {
-        if ch != '\n' {
+        if !state.is_line_break(ch) {
           string.push(ch);
         } else {
+          self.skip_crlf(state, ch);
           break;
         }
       }
@@ -85,6 +87,15 @@ pub trait Input {
     }
   }
 
+  #[inline]
+  fn skip_crlf(&mut self, state: &mut State, line_break: char) {
+    if let Some(ch) = self.peek(state, 0) {
+      if state.newline_mode().is_crlf(line_break, ch) {
+        self.read(state);
+      }
+    }
+  }
+
   #[inline]
   fn read_whitespace(&mut self, state: &mut State) -> Option<String> {
     if self.is_done(state) {
@@ -137,10 +148,18 @@ where
 {
   #[inline]
   fn peek(&mut self, state: &State, offset: usize) -> Option<char> {
-    self.peek_nth(state.index() + offset).map(Clone::clone)
+    self.peek_nth(state.index() + offset).copied()
   }
   #[inline]
   fn lines<'a>(&'a mut self, state: &'a mut State) -> Lines<'a> {
     Lines::new(self, state)
   }
 }
+
+#[inline]
+pub(crate) fn starts_with(input: &mut dyn Input, state: &State, string: &str) -> bool {
+  string
+    .chars()
+    .enumerate()
+    .all(|(offset, ch)| input.peek(state, offset) == Some(ch))
+}
@@ -0,0 +1,159 @@
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::vec::Vec;
+
+use peek_nth::IteratorExt;
+
+use super::read::read_reader;
+use super::{ReaderResult, Readers, State, TokenKind};
+
+#[inline]
+pub fn group_by_kind<I, K>(tokens: I) -> BTreeMap<&'static str, Vec<K>>
+where
+  I: IntoIterator<Item = K>,
+  K: TokenKind,
+{
+  let mut groups: BTreeMap<&'static str, Vec<K>> = BTreeMap::new();
+
+  for token in tokens {
+    groups.entry(token.kind_name()).or_default().push(token);
+  }
+
+  groups
+}
+
+#[inline]
+pub fn count_by_kind<I, K>(tokens: I) -> BTreeMap<&'static str, usize>
+where
+  I: IntoIterator<Item = K>,
+  K: TokenKind,
+{
+  let mut counts = BTreeMap::new();
+
+  for token in tokens {
+    *counts.entry(token.kind_name()).or_insert(0) += 1;
+  }
+
+  counts
+}
+
+#[inline]
+pub fn filter_kinds<'a, I, K>(tokens: I, kind_names: &'a [&'a str]) -> impl Iterator<Item = K> + 'a
+where
+  I: IntoIterator<Item = K>,
+  I::IntoIter: 'a,
+  K: TokenKind,
+{
+  tokens
+    .into_iter()
+    .filter(move |token| kind_names.contains(&token.kind_name()))
+}
+
+impl<T, E> Readers<T, E>
+where
+  T: TokenKind,
+{
+  /// Lexes `iter` and records, per reader index, the kinds of the tokens that
+  /// reader produced, stopping at the first error.
+  #[inline]
+  pub fn record_kinds<I>(&self, iter: I) -> Result<BTreeMap<usize, BTreeSet<&'static str>>, E>
+  where
+    I: Iterator<Item = char>,
+  {
+    let mut input = iter.peekable_nth();
+    let mut state = State::new();
+    let mut kinds: BTreeMap<usize, BTreeSet<&'static str>> = BTreeMap::new();
+
+    while let Some((index, result)) = read_reader(self, &mut input, &mut state) {
+      let names = kinds.entry(index).or_default();
+
+      match result {
+        ReaderResult::Some(token) => {
+          names.insert(token.kind_name());
+        }
+        ReaderResult::Many(results) => {
+          for result in results {
+            names.insert(result?.kind_name());
+          }
+        }
+        ReaderResult::Err(error) => return Err(error),
+        ReaderResult::Empty | ReaderResult::None => (),
+      }
+    }
+
+    Ok(kinds)
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::super::{Input, Reader, ReaderResult, ReadersBuilder};
+  use super::*;
+
+  #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+  enum Kind {
+    Letter,
+    Digit,
+    Other,
+  }
+
+  impl TokenKind for Kind {
+    fn kind_id(&self) -> usize {
+      *self as usize
+    }
+    fn kind_name(&self) -> &'static str {
+      match *self {
+        Kind::Letter => "Letter",
+        Kind::Digit => "Digit",
+        Kind::Other => "Other",
+      }
+    }
+  }
+
+  struct CharReader(fn(char) -> bool, Kind);
+
+  impl Reader<Kind, ()> for CharReader {
+    fn read(
+      &self,
+      _: &Readers<Kind, ()>,
+      input: &mut dyn Input,
+      _: &State,
+      next: &mut State,
+    ) -> ReaderResult<Kind, ()> {
+      match input.read(next) {
+        Some(ch) if (self.0)(ch) => ReaderResult::Some(self.1),
+        _ => ReaderResult::None,
+      }
+    }
+  }
+
+  #[test]
+  fn test_group_count_filter() {
+    let tokens = [Kind::Letter, Kind::Digit, Kind::Letter, Kind::Other];
+
+    let groups = group_by_kind(tokens.iter());
+    assert_eq!(groups["Letter"].len(), 2);
+    assert_eq!(groups["Digit"], [&Kind::Digit]);
+
+    let counts = count_by_kind(tokens.iter());
+    assert_eq!(counts["Letter"], 2);
+    assert_eq!(counts.get("Missing"), None);
+
+    let filtered: Vec<Kind> = filter_kinds(tokens.iter().cloned(), &["Digit", "Other"]).collect();
+    assert_eq!(filtered, [Kind::Digit, Kind::Other]);
+  }
+
+  #[test]
+  fn test_record_kinds() {
+    let readers = ReadersBuilder::new()
+      .add(CharReader(char::is_alphabetic, Kind::Letter))
+      .add(CharReader(char::is_numeric, Kind::Digit))
+      .add(CharReader(|_| true, Kind::Other))
+      .build();
+    let kinds = readers.record_kinds("a1-b".chars()).unwrap();
+
+    assert_eq!(kinds.len(), 3);
+    assert!(kinds[&0].contains("Letter"));
+    assert!(kinds[&1].contains("Digit"));
+    assert!(kinds[&2].contains("Other"));
+  }
+}
@@ -0,0 +1,9 @@
+use super::{Readers, Token, TokenError};
+
+/// Token value types that know how to build their own `Readers`, usually
+/// through `#[derive(Lexer)]`.
+pub trait Lexer: Sized {
+  type Error;
+
+  fn readers() -> Readers<Token<Self>, TokenError<Self::Error>>;
+}
@@ -1,33 +1,117 @@
 #![no_std]
 
 extern crate alloc;
+#[cfg(feature = "bumpalo")]
+extern crate bumpalo;
+#[cfg(feature = "std")]
+extern crate std;
 
 #[macro_use]
 extern crate serde;
+#[cfg(feature = "derive")]
+extern crate lexer_derive;
 extern crate peek_nth;
+#[cfg(feature = "regex")]
+extern crate regex_automata;
+extern crate spin;
+#[cfg(feature = "grapheme")]
+extern crate unicode_segmentation;
 
+#[cfg(feature = "bumpalo")]
+mod arena;
+mod attempt;
+mod attempt_result;
+pub mod combinator;
+mod highlight;
+mod indent_error;
+mod indent_iter;
+mod indentation;
 mod input;
+mod kinds;
+mod lexer;
 mod line;
 mod lines;
+mod named;
+#[cfg(feature = "parallel")]
+mod offset_input;
+#[cfg(feature = "parallel")]
+mod par_read;
+#[cfg(feature = "regex")]
+mod pattern;
+#[cfg(feature = "regex")]
+mod pattern_reader;
 mod read;
+mod read_results;
 mod reader;
+mod reader_fn;
 mod reader_result;
-mod readers;
+pub mod readers;
 mod readers_builder;
+mod readers_core;
+mod semantic_tokens;
+mod shadow_warning;
 mod state;
+mod symbol;
+mod symbol_table;
+mod sync_readers;
+mod sync_readers_builder;
+#[cfg(feature = "testing")]
+pub mod testing;
 mod token;
+mod token_buffer;
+mod token_buffer_iter;
 mod token_error;
+mod token_iter;
+mod token_kind;
 mod token_meta;
+#[cfg(feature = "std")]
+mod trace;
+#[cfg(feature = "std")]
+mod value_table;
 
+#[cfg(feature = "bumpalo")]
+pub use self::arena::Arena;
+pub use self::attempt::Attempt;
+pub use self::attempt_result::AttemptResult;
+pub use self::highlight::{highlight, HighlightFormat};
+pub use self::indent_error::IndentError;
+pub use self::indent_iter::IndentIter;
+pub use self::indentation::Indentation;
 pub use self::input::Input;
+pub use self::kinds::{count_by_kind, filter_kinds, group_by_kind};
+pub use self::lexer::Lexer;
 pub use self::line::Line;
 pub use self::lines::Lines;
-pub use self::read::read;
+pub use self::named::Named;
+#[cfg(feature = "regex")]
+pub use self::pattern::Pattern;
+#[cfg(feature = "regex")]
+pub use self::pattern_reader::PatternReader;
+pub use self::read::{read, read_many};
+pub use self::read_results::ReadResults;
 pub use self::reader::Reader;
+pub use self::reader_fn::{char_class, delimited, literal};
 pub use self::reader_result::ReaderResult;
-pub use self::readers::Readers;
 pub use self::readers_builder::ReadersBuilder;
-pub use self::state::State;
+pub use self::readers_core::{Observer, Readers};
+pub use self::semantic_tokens::{SemanticTokens, SemanticTokensEdit};
+pub use self::shadow_warning::ShadowWarning;
+pub use self::state::{ColumnMode, NewlineMode, State};
+pub use self::symbol::Symbol;
+pub use self::symbol_table::SymbolTable;
+pub use self::sync_readers::SyncReaders;
+pub use self::sync_readers_builder::SyncReadersBuilder;
 pub use self::token::Token;
+pub use self::token_buffer::TokenBuffer;
+pub use self::token_buffer_iter::TokenBufferIter;
 pub use self::token_error::TokenError;
+pub use self::token_iter::TokenIter;
+pub use self::token_kind::TokenKind;
 pub use self::token_meta::TokenMeta;
+#[cfg(feature = "std")]
+pub use self::trace::Trace;
+#[cfg(feature = "std")]
+pub use self::value_table::{ValueId, ValueTable};
+
+#[cfg(feature = "derive")]
+pub use lexer_derive::{Lexer, TokenKind};
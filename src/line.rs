@@ -28,7 +28,7 @@ impl PartialEq for Line {
 impl PartialOrd for Line {
   #[inline]
   fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-    self.line.partial_cmp(&other.line)
+    Some(self.cmp(other))
   }
 }
 
@@ -51,10 +51,7 @@ impl<'a> From<&'a str> for Line {
 impl From<(usize, String)> for Line {
   #[inline]
   fn from((offset, line): (usize, String)) -> Self {
-    Line {
-      offset: offset,
-      line: line,
-    }
+    Line { offset, line }
   }
 }
 
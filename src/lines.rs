@@ -8,10 +8,7 @@ pub struct Lines<'a> {
 impl<'a> Lines<'a> {
   #[inline(always)]
   pub fn new(input: &'a mut dyn Input, state: &'a mut State) -> Self {
-    Lines {
-      state: state,
-      input: input,
-    }
+    Lines { state, input }
   }
 
   #[inline]
@@ -36,7 +33,7 @@ impl<'a> Iterator for Lines<'a> {
 
 #[cfg(test)]
 mod test {
-  use super::super::Input;
+  use super::super::{Input, NewlineMode};
   use super::*;
   use peek_nth::IteratorExt;
 
@@ -51,4 +48,43 @@ mod test {
     assert_eq!(lines.next(), Some(Line::from("ghi")));
     assert_eq!(lines.next(), None);
   }
+
+  #[test]
+  fn test_lines_crlf() {
+    let mut input = "abc\r\ndef\rghi\r\n\r\njkl".chars().peekable_nth();
+    let mut state = State::new().with_newline_mode(NewlineMode::Crlf);
+
+    assert_eq!(input.peek_line(&state), Some(Line::from("abc")));
+
+    let mut lines = input.lines(&mut state);
+
+    assert_eq!(lines.next(), Some(Line::from("abc")));
+    assert_eq!(lines.next(), Some(Line::from("def")));
+    assert_eq!(lines.next(), Some(Line::from("ghi")));
+    assert_eq!(lines.next(), Some(Line::from("")));
+    assert_eq!(lines.next(), Some(Line::from("jkl")));
+    assert_eq!(lines.next(), None);
+    assert_eq!(state.row(), 5);
+  }
+
+  #[test]
+  fn test_lines_unicode() {
+    let mut input = "abc\u{2028}def\r\n".chars().peekable_nth();
+    let mut state = State::new().with_newline_mode(NewlineMode::Unicode);
+    let mut lines = input.lines(&mut state);
+
+    assert_eq!(lines.next(), Some(Line::from("abc")));
+    assert_eq!(lines.next(), Some(Line::from("def")));
+    assert_eq!(lines.next(), None);
+  }
+
+  #[test]
+  fn test_lines_lf_keeps_cr() {
+    let mut input = "abc\r\ndef".chars().peekable_nth();
+    let mut state = State::new();
+    let mut lines = input.lines(&mut state);
+
+    assert_eq!(lines.next(), Some(Line::from("abc\r")));
+    assert_eq!(lines.next(), Some(Line::from("def")));
+  }
 }
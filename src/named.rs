@@ -0,0 +1,60 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use super::{Input, Reader, ReaderResult, Readers, State};
+
+/// A reader with a name given to it, so it can be found by name in a
+/// `ReadersBuilder` or a `Readers`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Named<R> {
+  name: String,
+  reader: R,
+}
+
+impl<R> Named<R> {
+  #[inline]
+  pub fn new<S>(name: S, reader: R) -> Self
+  where
+    S: Into<String>,
+  {
+    Named {
+      name: name.into(),
+      reader,
+    }
+  }
+
+  #[inline(always)]
+  pub fn reader(&self) -> &R {
+    &self.reader
+  }
+  #[inline(always)]
+  pub fn into_reader(self) -> R {
+    self.reader
+  }
+}
+
+impl<T, E, R> Reader<T, E> for Named<R>
+where
+  R: Reader<T, E>,
+{
+  #[inline(always)]
+  fn read(
+    &self,
+    readers: &Readers<T, E>,
+    input: &mut dyn Input,
+    current: &State,
+    next: &mut State,
+  ) -> ReaderResult<T, E> {
+    self.reader.read(readers, input, current, next)
+  }
+
+  #[inline(always)]
+  fn name(&self) -> &str {
+    &self.name
+  }
+
+  #[inline(always)]
+  fn samples(&self) -> Vec<String> {
+    self.reader.samples()
+  }
+}
@@ -0,0 +1,40 @@
+use peek_nth::PeekableNth;
+
+use super::{Input, Lines, State};
+
+/// An `Input` over a slice of a larger text starting at char `offset`, so
+/// states keep counting from the start of the whole text.
+pub(crate) struct OffsetInput<I>
+where
+  I: Iterator<Item = char>,
+{
+  input: PeekableNth<I>,
+  offset: usize,
+}
+
+impl<I> OffsetInput<I>
+where
+  I: Iterator<Item = char>,
+{
+  #[inline]
+  pub(crate) fn new(input: PeekableNth<I>, offset: usize) -> Self {
+    OffsetInput { input, offset }
+  }
+}
+
+impl<I> Input for OffsetInput<I>
+where
+  I: Iterator<Item = char>,
+{
+  #[inline]
+  fn peek(&mut self, state: &State, offset: usize) -> Option<char> {
+    self
+      .input
+      .peek_nth(state.index() - self.offset + offset)
+      .copied()
+  }
+  #[inline]
+  fn lines<'a>(&'a mut self, state: &'a mut State) -> Lines<'a> {
+    Lines::new(self, state)
+  }
+}
@@ -0,0 +1,226 @@
+use alloc::vec::Vec;
+use std::panic;
+use std::thread;
+
+use peek_nth::IteratorExt;
+
+use super::offset_input::OffsetInput;
+use super::read::read_reader;
+use super::{ReaderResult, State, SyncReaders};
+
+impl<T, E> SyncReaders<T, E>
+where
+  T: Send,
+  E: Send,
+{
+  /// Lexes `input` on as many threads as are available, see
+  /// `par_read_with_state`.
+  #[inline]
+  pub fn par_read<P>(&self, input: &str, is_boundary: P) -> Vec<Result<T, E>>
+  where
+    P: FnMut(char) -> bool,
+  {
+    self.par_read_with_state(input, State::new(), is_boundary)
+  }
+
+  /// Splits `input` into chunks, one per available thread, and lexes them
+  /// on scoped threads, returning the same tokens `read_with_state` would.
+  /// `is_boundary` is called on every char in order and returns true where
+  /// the input may be split after that char, so no token or reader
+  /// lookahead may cross that point.
+  #[inline]
+  pub fn par_read_with_state<P>(
+    &self,
+    input: &str,
+    state: State,
+    is_boundary: P,
+  ) -> Vec<Result<T, E>>
+  where
+    P: FnMut(char) -> bool,
+  {
+    let threads = thread::available_parallelism().map_or(1, |threads| threads.get());
+    self.par_read_chunks(input, state, threads, is_boundary)
+  }
+
+  #[inline]
+  fn par_read_chunks<P>(
+    &self,
+    input: &str,
+    state: State,
+    chunks: usize,
+    mut is_boundary: P,
+  ) -> Vec<Result<T, E>>
+  where
+    P: FnMut(char) -> bool,
+  {
+    let chunk_len = (input.len() / chunks.max(1)).max(1);
+    let mut splits = Vec::with_capacity(chunks);
+    let mut start = 0;
+    let mut start_state = state.clone();
+    let mut state = state;
+
+    for (index, ch) in input.char_indices() {
+      let end = index + ch.len_utf8();
+
+      state.read(ch);
+
+      if is_boundary(ch) && end - start >= chunk_len && end < input.len() {
+        splits.push((&input[start..end], start_state));
+        start = end;
+        start_state = state.clone();
+      }
+    }
+    splits.push((&input[start..], start_state));
+
+    if splits.len() == 1 {
+      return splits
+        .pop()
+        .map(|(chunk, state)| self.read_chunk(chunk, state))
+        .unwrap_or_default();
+    }
+
+    thread::scope(|scope| {
+      let handles: Vec<_> = splits
+        .into_iter()
+        .map(|(chunk, state)| scope.spawn(move || self.read_chunk(chunk, state)))
+        .collect();
+
+      handles
+        .into_iter()
+        .flat_map(|handle| {
+          handle
+            .join()
+            .unwrap_or_else(|error| panic::resume_unwind(error))
+        })
+        .collect()
+    })
+  }
+
+  #[inline]
+  fn read_chunk(&self, chunk: &str, state: State) -> Vec<Result<T, E>> {
+    let readers = self.readers();
+    let mut input = OffsetInput::new(chunk.chars().peekable_nth(), state.index());
+    let mut state = state;
+    let mut results = Vec::new();
+
+    while let Some((_, result)) = read_reader(&readers, &mut input, &mut state) {
+      match result {
+        ReaderResult::Some(token) => results.push(Ok(token)),
+        ReaderResult::Err(error) => results.push(Err(error)),
+        ReaderResult::Many(many) => results.extend(many),
+        ReaderResult::Empty | ReaderResult::None => (),
+      }
+    }
+
+    results
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use alloc::string::String;
+
+  use super::super::readers::{
+    Number, NumberLiteral, NumberReader, ReadError, StringReader, WhitespaceReader,
+  };
+  use super::super::{ColumnMode, NewlineMode, SyncReadersBuilder, Token, TokenMeta};
+  use super::*;
+
+  #[derive(Debug, Clone, PartialEq)]
+  enum Value {
+    Number(i64),
+    String(String),
+    Char(char),
+  }
+
+  fn readers() -> SyncReaders<Token<Value>, (ReadError, TokenMeta)> {
+    SyncReadersBuilder::new()
+      .add(WhitespaceReader::new())
+      .add(NumberReader::new(
+        |literal: NumberLiteral, meta| match literal.into_value() {
+          Number::Integer(n) => Token::new(meta, Value::Number(n)),
+          _ => Token::new(meta, Value::Number(0)),
+        },
+        |error, meta| (error, meta),
+      ))
+      .add(StringReader::new(
+        |string, meta| Token::new(meta, Value::String(string)),
+        |error, meta| (error, meta),
+      ))
+      .add_fn(|_, input, current, next| match input.read(next) {
+        Some(ch) => ReaderResult::Some(Token::new(
+          TokenMeta::new_state_meta(current, next),
+          Value::Char(ch),
+        )),
+        None => ReaderResult::None,
+      })
+      .build()
+  }
+
+  fn source() -> String {
+    let mut source = String::new();
+
+    for i in 0..200 {
+      source.push_str(&alloc::format!(
+        "({} \"é\tline\n{}\" (\t{}))\r\n",
+        i,
+        i * 7,
+        i * 13
+      ));
+      if i % 17 == 0 {
+        source.push_str("\"bad \\q\"\n");
+      }
+    }
+
+    source
+  }
+
+  fn is_boundary() -> impl FnMut(char) -> bool {
+    let mut depth = 0i32;
+    let mut in_string = false;
+
+    move |ch| {
+      match ch {
+        '"' => in_string = !in_string,
+        '(' if !in_string => depth += 1,
+        ')' if !in_string => depth -= 1,
+        _ => (),
+      }
+      ch == '\n' && depth == 0 && !in_string
+    }
+  }
+
+  #[test]
+  fn test_par_read_matches_read() {
+    let readers = readers();
+    let source = source();
+
+    for state in [
+      State::new(),
+      State::new()
+        .with_column_mode(ColumnMode::Visual(4))
+        .with_newline_mode(NewlineMode::Crlf),
+    ] {
+      let expected: Vec<_> = readers
+        .readers()
+        .read_with_state(source.chars(), state.clone())
+        .collect();
+
+      for chunks in [1, 2, 3, 8, 64] {
+        assert_eq!(
+          readers.par_read_chunks(&source, state.clone(), chunks, is_boundary()),
+          expected
+        );
+      }
+      assert_eq!(
+        readers.par_read_with_state(&source, state, is_boundary()),
+        expected
+      );
+    }
+  }
+
+  #[test]
+  fn test_par_read_empty() {
+    assert_eq!(readers().par_read("", is_boundary()), []);
+  }
+}
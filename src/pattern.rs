@@ -0,0 +1,241 @@
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
+use regex_automata::dfa::{dense, Automaton, StartKind};
+use regex_automata::util::start;
+use regex_automata::{Anchored, MatchKind};
+
+use super::{Input, State};
+
+/// A literal or regular expression matched against an `Input` from a
+/// `State`, the building block of `PatternReader`. Regexes match as much as
+/// they can, so `a|ab` matches all of `ab` rather than stopping at the first
+/// alternative like the `regex` crate would. The char before the state is
+/// looked behind at, so `^`, `\b` and `\B` only match where they would in the
+/// whole input.
+pub enum Pattern {
+  Literal(String),
+  Regex(String, Box<dense::DFA<Vec<u32>>>),
+  Compiled(&'static str, Box<dense::DFA<&'static [u32]>>),
+}
+
+impl Pattern {
+  #[inline]
+  pub fn literal<S>(literal: S) -> Self
+  where
+    S: Into<String>,
+  {
+    Pattern::Literal(literal.into())
+  }
+
+  #[inline]
+  pub fn regex<S>(regex: S) -> Result<Self, String>
+  where
+    S: Into<String>,
+  {
+    let regex = regex.into();
+
+    dense::Builder::new()
+      .configure(config())
+      .build(&regex)
+      .map(|dfa| Pattern::Regex(regex, Box::new(dfa)))
+      .map_err(|error| alloc::format!("{}", error))
+  }
+
+  /// A regex built ahead of time, from the bytes of a dense DFA serialized
+  /// with the native endianness and built with `MatchKind::All` and anchored
+  /// starts, like `#[derive(Lexer)]` does at compile time. `bytes` has to be
+  /// aligned to 4 bytes.
+  #[inline]
+  pub fn compiled(regex: &'static str, bytes: &'static [u8]) -> Result<Self, String> {
+    dense::DFA::from_bytes(bytes)
+      .map(|(dfa, _)| Pattern::Compiled(regex, Box::new(dfa)))
+      .map_err(|error| alloc::format!("{}", error))
+  }
+
+  #[inline]
+  pub fn as_str(&self) -> &str {
+    match self {
+      Pattern::Literal(literal) => literal,
+      Pattern::Regex(regex, _) => regex,
+      Pattern::Compiled(regex, _) => regex,
+    }
+  }
+
+  /// The length in chars of the longest match at `state`, if any.
+  #[inline]
+  pub fn find(&self, input: &mut dyn Input, state: &State) -> Option<usize> {
+    match self {
+      Pattern::Literal(literal) => find_literal(literal, input, state),
+      Pattern::Regex(_, dfa) => find_regex(&**dfa, input, state),
+      Pattern::Compiled(_, dfa) => find_regex(&**dfa, input, state),
+    }
+  }
+}
+
+/// The DFA config every regex pattern is built with, keeping all the matches
+/// so the longest can be picked.
+#[inline]
+fn config() -> dense::Config {
+  dense::Config::new()
+    .start_kind(StartKind::Anchored)
+    .match_kind(MatchKind::All)
+}
+
+#[inline]
+fn find_literal(literal: &str, input: &mut dyn Input, state: &State) -> Option<usize> {
+  let mut len = 0;
+
+  for ch in literal.chars() {
+    if input.peek(state, len) != Some(ch) {
+      return None;
+    }
+    len += 1;
+  }
+
+  if len == 0 {
+    None
+  } else {
+    Some(len)
+  }
+}
+
+#[inline]
+fn find_regex<A>(dfa: &A, input: &mut dyn Input, state: &State) -> Option<usize>
+where
+  A: Automaton,
+{
+  let mut buf = [0u8; 4];
+  let look_behind = state
+    .prev()
+    .and_then(|ch| ch.encode_utf8(&mut buf).bytes().last());
+  let config = start::Config::new()
+    .anchored(Anchored::Yes)
+    .look_behind(look_behind);
+  let mut id = dfa.start_state(&config).ok()?;
+  let mut matched = None;
+  let mut offset = 0;
+
+  while let Some(ch) = input.peek(state, offset) {
+    for (index, byte) in ch.encode_utf8(&mut buf).bytes().enumerate() {
+      id = dfa.next_state(id, byte);
+
+      if dfa.is_special_state(id) {
+        if dfa.is_match_state(id) {
+          if index == 0 {
+            matched = Some(offset);
+          }
+        } else if dfa.is_dead_state(id) || dfa.is_quit_state(id) {
+          return matched.filter(|&len| len > 0);
+        }
+      }
+    }
+    offset += 1;
+  }
+
+  if dfa.is_match_state(dfa.next_eoi_state(id)) {
+    matched = Some(offset);
+  }
+
+  matched.filter(|&len| len > 0)
+}
+
+impl fmt::Debug for Pattern {
+  #[inline]
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      Pattern::Literal(literal) => write!(f, "Literal({:?})", literal),
+      Pattern::Regex(..) | Pattern::Compiled(..) => write!(f, "Regex({:?})", self.as_str()),
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use peek_nth::IteratorExt;
+
+  fn find(pattern: &Pattern, string: &str) -> Option<usize> {
+    pattern.find(&mut string.chars().peekable_nth(), &State::new())
+  }
+
+  #[test]
+  fn test_literal() {
+    let pattern = Pattern::literal("==");
+
+    assert_eq!(find(&pattern, "==="), Some(2));
+    assert_eq!(find(&pattern, "=!"), None);
+    assert_eq!(find(&pattern, "="), None);
+  }
+
+  #[test]
+  fn test_regex() {
+    let pattern = Pattern::regex("[0-9]+(\\.[0-9]+)?").unwrap();
+
+    assert_eq!(find(&pattern, "12.5x"), Some(4));
+    assert_eq!(find(&pattern, "12."), Some(2));
+    assert_eq!(find(&pattern, "x12"), None);
+
+    let pattern = Pattern::regex("\\p{L}+").unwrap();
+    assert_eq!(find(&pattern, "héllo wörld"), Some(5));
+
+    let pattern = Pattern::regex("a*").unwrap();
+    assert_eq!(find(&pattern, "b"), None);
+
+    assert!(Pattern::regex("[").is_err());
+  }
+
+  #[test]
+  fn test_regex_longest_match() {
+    let pattern = Pattern::regex("a|ab").unwrap();
+    assert_eq!(find(&pattern, "abc"), Some(2));
+
+    let pattern = Pattern::regex("[a-z]+?").unwrap();
+    assert_eq!(find(&pattern, "abc1"), Some(3));
+  }
+
+  #[test]
+  fn test_look_behind() {
+    let find_at = |pattern: &Pattern, string: &str, index: usize| {
+      let mut input = string.chars().peekable_nth();
+      let mut state = State::new();
+
+      for _ in 0..index {
+        input.read(&mut state);
+      }
+      pattern.find(&mut input, &state)
+    };
+
+    let pattern = Pattern::regex("^a").unwrap();
+    assert_eq!(find_at(&pattern, "aa", 0), Some(1));
+    assert_eq!(find_at(&pattern, "aa", 1), None);
+
+    let pattern = Pattern::regex("(?m)^a").unwrap();
+    assert_eq!(find_at(&pattern, "\na", 1), Some(1));
+
+    let pattern = Pattern::regex("(?-u:\\b)b").unwrap();
+    assert_eq!(find_at(&pattern, "ab b", 1), None);
+    assert_eq!(find_at(&pattern, "ab b", 3), Some(1));
+
+    let pattern = Pattern::regex("(?-u:\\B)b").unwrap();
+    assert_eq!(find_at(&pattern, "ab b", 1), Some(1));
+    assert_eq!(find_at(&pattern, "ab b", 3), None);
+  }
+
+  #[test]
+  fn test_compiled() {
+    let (bytes, pad) = dense::Builder::new()
+      .configure(config())
+      .build("a|ab")
+      .unwrap()
+      .to_bytes_native_endian();
+    let bytes: &'static [u8] = &bytes.leak()[pad..];
+    let pattern = Pattern::compiled("a|ab", bytes).unwrap();
+
+    assert_eq!(pattern.as_str(), "a|ab");
+    assert_eq!(find(&pattern, "abc"), Some(2));
+    assert_eq!(find(&pattern, "b"), None);
+  }
+}
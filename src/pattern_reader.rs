@@ -0,0 +1,151 @@
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use super::{Input, Pattern, Reader, ReaderResult, Readers, State, Token, TokenError, TokenMeta};
+
+type TokenFn<T, E> = Box<dyn Fn(&str) -> Result<T, E> + Send + Sync>;
+
+enum Rule<T, E> {
+  Skip,
+  Token(TokenFn<T, E>),
+}
+
+/// Tries all of its patterns at once and keeps the longest match, ties going
+/// to the pattern added first. Input no pattern matches is reported as an
+/// `"unexpected character"` error, converted into `E`.
+pub struct PatternReader<T, E = &'static str> {
+  rules: Vec<(Pattern, Rule<T, E>)>,
+}
+
+impl<T, E> Default for PatternReader<T, E> {
+  #[inline]
+  fn default() -> Self {
+    PatternReader { rules: Vec::new() }
+  }
+}
+
+impl<T, E> PatternReader<T, E> {
+  #[inline]
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  #[inline]
+  pub fn skip(mut self, pattern: Pattern) -> Self {
+    self.rules.push((pattern, Rule::Skip));
+    self
+  }
+
+  #[inline]
+  pub fn token<F>(mut self, pattern: Pattern, f: F) -> Self
+  where
+    F: 'static + Send + Sync + Fn(&str) -> Result<T, E>,
+  {
+    self.rules.push((pattern, Rule::Token(Box::new(f))));
+    self
+  }
+}
+
+impl<T, E> Reader<Token<T>, TokenError<E>> for PatternReader<T, E>
+where
+  E: From<&'static str>,
+{
+  #[inline]
+  fn read(
+    &self,
+    _: &Readers<Token<T>, TokenError<E>>,
+    input: &mut dyn Input,
+    current: &State,
+    next: &mut State,
+  ) -> ReaderResult<Token<T>, TokenError<E>> {
+    let mut longest: Option<(usize, &Rule<T, E>)> = None;
+
+    for (pattern, rule) in &self.rules {
+      if let Some(len) = pattern.find(input, current) {
+        // `Option::is_none_or` needs Rust 1.82
+        #[allow(clippy::unnecessary_map_or)]
+        let is_longest = longest.map_or(true, |(longest_len, _)| len > longest_len);
+
+        if is_longest {
+          longest = Some((len, rule));
+        }
+      }
+    }
+
+    match longest {
+      Some((len, rule)) => {
+        let mut string = String::new();
+
+        for _ in 0..len {
+          if let Some(ch) = input.read(next) {
+            string.push(ch);
+          }
+        }
+
+        match rule {
+          Rule::Skip => ReaderResult::Empty,
+          Rule::Token(f) => {
+            let meta = TokenMeta::new_state_meta(current, next);
+
+            match f(&string) {
+              Ok(value) => ReaderResult::Some(Token::new(meta, value)),
+              Err(error) => ReaderResult::Err(TokenError::new(meta, error)),
+            }
+          }
+        }
+      }
+      None => match input.read(next) {
+        Some(_) => ReaderResult::Err(TokenError::new(
+          TokenMeta::new_state_meta(current, next),
+          "unexpected character".into(),
+        )),
+        None => ReaderResult::None,
+      },
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::super::ReadersBuilder;
+  use super::*;
+
+  #[derive(Debug, PartialEq)]
+  enum Value {
+    If,
+    Identifier(String),
+  }
+
+  #[test]
+  fn test_longest_match() {
+    let readers = ReadersBuilder::new()
+      .add(
+        PatternReader::new()
+          .skip(Pattern::regex("\\s+").unwrap())
+          .token(Pattern::literal("if"), |_| Ok(Value::If))
+          .token(Pattern::regex("[a-z]+").unwrap(), |s| {
+            Ok(Value::Identifier(s.into()))
+          }),
+      )
+      .build();
+
+    let values: Vec<Result<Value, &'static str>> = readers
+      .read("if iffy ?".chars())
+      .map(|result| {
+        result
+          .map(Token::into_value)
+          .map_err(|error| *error.error())
+      })
+      .collect();
+
+    assert_eq!(
+      values,
+      [
+        Ok(Value::If),
+        Ok(Value::Identifier("iffy".into())),
+        Err("unexpected character")
+      ]
+    );
+  }
+}
@@ -1,53 +1,275 @@
-use super::{Input, ReaderResult, Readers, State};
+use super::{Attempt, Input, ReadResults, ReaderResult, Readers, State};
 
+/// Reads the next token or error, which is how a reader reads the tokens
+/// nested inside it. Of a reader returning `ReaderResult::Many` only the
+/// first result is returned, use `read_many` to get them all.
 #[inline]
 pub fn read<T, E>(
   readers: &Readers<T, E>,
   input: &mut dyn Input,
   state: &mut State,
 ) -> Option<Result<T, E>> {
+  read_many(readers, input, state).and_then(|mut results| results.next())
+}
+
+/// Reads all the tokens and errors the next reader returns.
+#[inline]
+pub fn read_many<T, E>(
+  readers: &Readers<T, E>,
+  input: &mut dyn Input,
+  state: &mut State,
+) -> Option<ReadResults<T, E>> {
+  read_reader(readers, input, state).map(|(_, result)| match result {
+    ReaderResult::Some(token) => ReadResults::one(Ok(token)),
+    ReaderResult::Err(error) => ReadResults::one(Err(error)),
+    ReaderResult::Many(results) => ReadResults::many(results),
+    ReaderResult::Empty | ReaderResult::None => unreachable!(),
+  })
+}
+
+/// Runs the readers until one returns tokens or an error, returning its
+/// index and a `Some`, `Err` or non empty `Many` result.
+#[inline]
+pub(crate) fn read_reader<T, E>(
+  readers: &Readers<T, E>,
+  input: &mut dyn Input,
+  state: &mut State,
+) -> Option<(usize, ReaderResult<T, E>)> {
+  loop {
+    match read_step(readers, input, state) {
+      Some((_, ReaderResult::Empty)) => (),
+      Some((_, ReaderResult::Many(ref results))) if results.is_empty() => (),
+      result => return result,
+    }
+  }
+}
+
+/// Runs the readers once, returning the index of the reader that matched and
+/// its result, which is never `ReaderResult::None`.
+#[inline]
+pub(crate) fn read_step<T, E>(
+  readers: &Readers<T, E>,
+  input: &mut dyn Input,
+  state: &mut State,
+) -> Option<(usize, ReaderResult<T, E>)> {
   if input.is_done(state) {
     None
   } else {
-    let mut token = None;
-    let mut new_state = None;
-    let mut is_empty = false;
     let orig_state = state.clone();
 
-    for reader in readers.iter() {
+    for (index, reader) in readers.iter().enumerate() {
       let mut next_state = orig_state.clone();
 
-      match reader.read(readers, input, &orig_state, &mut next_state) {
-        ReaderResult::Some(t) => {
-          token = Some(Ok(t));
-          new_state = Some(next_state);
-          break;
-        }
-        ReaderResult::Err(e) => {
-          return Some(Err(e));
-        }
-        ReaderResult::Empty => {
-          new_state = Some(next_state);
-          is_empty = true;
-          break;
-        }
+      let result = reader.read(readers, input, &orig_state, &mut next_state);
+
+      if let Some(observer) = readers.observer() {
+        observer(&Attempt::new(
+          index,
+          reader.name(),
+          &orig_state,
+          &next_state,
+          (&result).into(),
+        ));
+      }
+
+      match result {
         ReaderResult::None => (),
+        result => {
+          state.clone_from(&next_state);
+          debug_assert!(
+            orig_state.index() != state.index() || input.is_done(state),
+            "{} returned a result at {:?} without reading anything",
+            reader.name(),
+            orig_state
+          );
+          return Some((index, result));
+        }
       }
     }
 
-    if let Some(s) = new_state {
-      state.clone_from(&s);
-    }
+    debug_assert!(
+      input.is_done(state),
+      "No reader was able to read at {:?}",
+      orig_state
+    );
+    None
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use alloc::string::String;
+  use alloc::vec;
+  use alloc::vec::Vec;
+
+  use peek_nth::IteratorExt;
+
+  use super::super::{Reader, ReadersBuilder};
+  use super::*;
 
-    if is_empty {
-      read(readers, input, state)
-    } else {
-      debug_assert!(
-        orig_state.index() != state.index() || input.is_done(&state),
-        "No reader was able to read at {:?}",
-        orig_state
-      );
-      token
+  fn readers() -> Readers<(String, u64), ()> {
+    ReadersBuilder::new()
+      .add_fn(|_, input, current, next| {
+        if input.peek(current, 0) == Some('>') && input.peek(current, 1) == Some('>') {
+          let mut tokens = Vec::new();
+
+          for _ in 0..2 {
+            let start = next.clone();
+            input.read(next);
+            tokens.push(Ok((">".into(), start.index() as u64)));
+          }
+
+          ReaderResult::Many(tokens)
+        } else if input.peek(current, 0) == Some('_') {
+          input.read(next);
+          ReaderResult::Many(Vec::new())
+        } else if input.peek(current, 0) == Some('!') {
+          input.read(next);
+          ReaderResult::Many(vec![Err(()), Ok(("!".into(), current.index() as u64))])
+        } else {
+          match input.read(next) {
+            Some(ch) => ReaderResult::Some((ch.into(), current.index() as u64)),
+            None => ReaderResult::None,
+          }
+        }
+      })
+      .build()
+  }
+
+  #[test]
+  fn test_token_iter_many() {
+    let results: Vec<_> = readers().read("a>>_!b".chars()).collect();
+
+    assert_eq!(
+      results,
+      [
+        Ok(("a".into(), 0)),
+        Ok((">".into(), 1)),
+        Ok((">".into(), 2)),
+        Err(()),
+        Ok(("!".into(), 4)),
+        Ok(("b".into(), 5))
+      ]
+    );
+  }
+
+  #[test]
+  fn test_read_many() {
+    let readers = readers();
+    let mut input = ">>_a".chars().peekable_nth();
+    let mut state = State::new();
+
+    assert_eq!(
+      read_many(&readers, &mut input, &mut state).map(Iterator::collect::<Vec<_>>),
+      Some(vec![Ok((">".into(), 0)), Ok((">".into(), 1))])
+    );
+    assert_eq!(
+      read_many(&readers, &mut input, &mut state).map(Iterator::collect::<Vec<_>>),
+      Some(vec![Ok(("a".into(), 3))])
+    );
+    assert!(read_many(&readers, &mut input, &mut state).is_none());
+  }
+
+  #[test]
+  fn test_read() {
+    let readers = readers();
+    let mut input = "a!>>".chars().peekable_nth();
+    let mut state = State::new();
+
+    assert_eq!(
+      read(&readers, &mut input, &mut state),
+      Some(Ok(("a".into(), 0)))
+    );
+    assert_eq!(read(&readers, &mut input, &mut state), Some(Err(())));
+    assert_eq!(
+      read(&readers, &mut input, &mut state),
+      Some(Ok((">".into(), 2)))
+    );
+    assert_eq!(read(&readers, &mut input, &mut state), None);
+  }
+
+  struct CharReader;
+
+  impl Reader<char, char> for CharReader {
+    fn read(
+      &self,
+      _: &Readers<char, char>,
+      input: &mut dyn Input,
+      _: &State,
+      next: &mut State,
+    ) -> ReaderResult<char, char> {
+      match input.read(next) {
+        Some('x') => ReaderResult::Err('x'),
+        Some(ch) => ReaderResult::Some(ch),
+        None => ReaderResult::None,
+      }
     }
   }
+
+  #[test]
+  fn test_read_past_error() {
+    let readers = ReadersBuilder::new().add(CharReader).build();
+
+    let results: Vec<_> = readers.read("axxb".chars()).collect();
+
+    assert_eq!(results, [Ok('a'), Err('x'), Err('x'), Ok('b')]);
+  }
+
+  #[test]
+  #[cfg(debug_assertions)]
+  #[should_panic(expected = "without reading anything")]
+  fn test_no_progress() {
+    let readers = ReadersBuilder::<(), ()>::new()
+      .add_fn(|_, _, _, _| ReaderResult::Empty)
+      .build();
+
+    let _ = readers.read("a".chars()).next();
+  }
+
+  #[test]
+  fn test_read_many_nested() {
+    let readers = ReadersBuilder::<String, ()>::new()
+      .add_fn(|readers, input, current, next| {
+        if input.peek(current, 0) != Some('[') {
+          return ReaderResult::None;
+        }
+        input.read(next);
+
+        let mut children = Vec::new();
+
+        while input.peek(next, 0) != Some(']') {
+          match read_many(readers, input, next) {
+            Some(results) => {
+              for result in results {
+                match result {
+                  Ok(token) => children.push(token),
+                  Err(error) => return ReaderResult::Err(error),
+                }
+              }
+            }
+            None => return ReaderResult::Err(()),
+          }
+        }
+        input.read(next);
+
+        ReaderResult::Some(children.join(","))
+      })
+      .add_fn(|_, input, current, next| {
+        if input.peek(current, 0) == Some('>') && input.peek(current, 1) == Some('>') {
+          input.read(next);
+          input.read(next);
+          ReaderResult::Many(vec![Ok(">".into()), Ok(">".into())])
+        } else {
+          match input.read(next) {
+            Some(ch) => ReaderResult::Some(ch.into()),
+            None => ReaderResult::None,
+          }
+        }
+      })
+      .build();
+
+    let tokens: Vec<_> = readers.read("[a>>b]".chars()).map(Result::unwrap).collect();
+
+    assert_eq!(tokens, ["a,>,>,b"]);
+  }
 }
@@ -0,0 +1,49 @@
+use alloc::vec;
+
+/// The tokens and errors one reader returned, in order, see `read_many`.
+/// Holds a single result without allocating.
+#[derive(Debug, Clone)]
+pub struct ReadResults<T, E>(Results<T, E>);
+
+#[derive(Debug, Clone)]
+enum Results<T, E> {
+  One(Option<Result<T, E>>),
+  Many(vec::IntoIter<Result<T, E>>),
+}
+
+impl<T, E> ReadResults<T, E> {
+  #[inline(always)]
+  pub(crate) fn one(result: Result<T, E>) -> Self {
+    ReadResults(Results::One(Some(result)))
+  }
+
+  #[inline(always)]
+  pub(crate) fn many(results: vec::Vec<Result<T, E>>) -> Self {
+    ReadResults(Results::Many(results.into_iter()))
+  }
+}
+
+impl<T, E> Iterator for ReadResults<T, E> {
+  type Item = Result<T, E>;
+
+  #[inline]
+  fn next(&mut self) -> Option<Self::Item> {
+    match self.0 {
+      Results::One(ref mut result) => result.take(),
+      Results::Many(ref mut results) => results.next(),
+    }
+  }
+
+  #[inline]
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    match self.0 {
+      Results::One(ref result) => {
+        let len = result.is_some() as usize;
+        (len, Some(len))
+      }
+      Results::Many(ref results) => results.size_hint(),
+    }
+  }
+}
+
+impl<T, E> ExactSizeIterator for ReadResults<T, E> {}
@@ -1,3 +1,6 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
 use super::{Input, ReaderResult, Readers, State};
 
 pub trait Reader<T, E> {
@@ -8,4 +11,78 @@ pub trait Reader<T, E> {
     current: &State,
     next: &mut State,
   ) -> ReaderResult<T, E>;
+
+  /// A name for diagnostics, tracing and finding readers by name, by
+  /// default the type name without its path and generic arguments, so
+  /// `literal` for the readers `literal` returns. Readers of the same type
+  /// share a name, give them their own with `Named`.
+  #[inline]
+  fn name(&self) -> &str {
+    short_name(core::any::type_name::<Self>())
+  }
+
+  /// Inputs this reader reads, used to warn about readers tried before it
+  /// that read them too.
+  #[inline]
+  fn samples(&self) -> Vec<String> {
+    Vec::new()
+  }
+}
+
+impl<T, E, F> Reader<T, E> for F
+where
+  F: Fn(&Readers<T, E>, &mut dyn Input, &State, &mut State) -> ReaderResult<T, E>,
+{
+  #[inline(always)]
+  fn read(
+    &self,
+    readers: &Readers<T, E>,
+    input: &mut dyn Input,
+    current: &State,
+    next: &mut State,
+  ) -> ReaderResult<T, E> {
+    self(readers, input, current, next)
+  }
+}
+
+/// The last path segment of a type name without its generic arguments,
+/// closures taking the name of the function they are in.
+#[inline]
+fn short_name(name: &str) -> &str {
+  let mut name = name.split('<').next().unwrap_or(name);
+
+  while let Some(parent) = name.strip_suffix("::{{closure}}") {
+    name = parent;
+  }
+  name.rsplit("::").next().unwrap_or(name)
+}
+
+#[cfg(test)]
+mod test {
+  use super::super::readers::WhitespaceReader;
+  use super::super::{char_class, literal};
+  use super::*;
+
+  fn name<R: Reader<(), ()>>(reader: R) -> String {
+    reader.name().into()
+  }
+
+  #[test]
+  fn test_short_name() {
+    assert_eq!(short_name("lexer::readers::Foo<lexer::Bar>"), "Foo");
+    assert_eq!(short_name("a::b::c::{{closure}}"), "c");
+    assert_eq!(short_name("a::c::{{closure}}::{{closure}}"), "c");
+    assert_eq!(short_name("Foo"), "Foo");
+  }
+
+  #[test]
+  fn test_default_name() {
+    assert_eq!(name(WhitespaceReader::new()), "WhitespaceReader");
+    assert_eq!(name(literal("a", |_| ())), "literal");
+    assert_eq!(name(char_class(|_| true, |_, _| ())), "char_class");
+    assert_eq!(
+      name(|_: &Readers<(), ()>, _: &mut dyn Input, _: &State, _: &mut State| ReaderResult::None),
+      "test_default_name"
+    );
+  }
 }
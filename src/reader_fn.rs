@@ -0,0 +1,158 @@
+use alloc::string::String;
+
+use super::input::starts_with;
+use super::{Input, ReaderResult, Readers, State, TokenMeta};
+
+/// A reader for exactly `literal`.
+#[inline]
+pub fn literal<T, E, S, F>(
+  literal: S,
+  f: F,
+) -> impl Fn(&Readers<T, E>, &mut dyn Input, &State, &mut State) -> ReaderResult<T, E>
+where
+  S: Into<String>,
+  F: Fn(TokenMeta) -> T,
+{
+  let literal = literal.into();
+
+  move |_: &Readers<T, E>, input: &mut dyn Input, current: &State, next: &mut State| {
+    if literal.is_empty() || !starts_with(input, current, &literal) {
+      ReaderResult::None
+    } else {
+      input.read_offset(next, literal.chars().count());
+      ReaderResult::Some(f(TokenMeta::new_state_meta(current, next)))
+    }
+  }
+}
+
+/// A reader for one or more chars matching `pred`.
+#[inline]
+pub fn char_class<T, E, P, F>(
+  pred: P,
+  f: F,
+) -> impl Fn(&Readers<T, E>, &mut dyn Input, &State, &mut State) -> ReaderResult<T, E>
+where
+  P: Fn(char) -> bool,
+  F: Fn(String, TokenMeta) -> T,
+{
+  move |_: &Readers<T, E>, input: &mut dyn Input, current: &State, next: &mut State| {
+    let mut string = String::new();
+
+    while let Some(ch) = input.peek(next, 0) {
+      if pred(ch) {
+        input.read(next);
+        string.push(ch);
+      } else {
+        break;
+      }
+    }
+
+    if string.is_empty() {
+      ReaderResult::None
+    } else {
+      ReaderResult::Some(f(string, TokenMeta::new_state_meta(current, next)))
+    }
+  }
+}
+
+/// A reader for everything from `open` up to and including the next `close`,
+/// `f` gets the text in between. Input where `close` never comes is left to
+/// the following readers.
+#[inline]
+pub fn delimited<T, E, S, F>(
+  open: S,
+  close: S,
+  f: F,
+) -> impl Fn(&Readers<T, E>, &mut dyn Input, &State, &mut State) -> ReaderResult<T, E>
+where
+  S: Into<String>,
+  F: Fn(String, TokenMeta) -> T,
+{
+  let open = open.into();
+  let close = close.into();
+
+  move |_: &Readers<T, E>, input: &mut dyn Input, current: &State, next: &mut State| {
+    if open.is_empty() || close.is_empty() || !starts_with(input, current, &open) {
+      return ReaderResult::None;
+    }
+
+    input.read_offset(next, open.chars().count());
+
+    let mut string = String::new();
+
+    loop {
+      if starts_with(input, next, &close) {
+        input.read_offset(next, close.chars().count());
+        return ReaderResult::Some(f(string, TokenMeta::new_state_meta(current, next)));
+      }
+
+      match input.read(next) {
+        Some(ch) => string.push(ch),
+        None => return ReaderResult::None,
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::super::{ReadersBuilder, Token};
+  use super::*;
+  use alloc::vec::Vec;
+
+  #[derive(Debug, PartialEq)]
+  enum Value {
+    Arrow,
+    Word(String),
+    Comment(String),
+  }
+
+  #[test]
+  fn test_small_grammar() {
+    let readers = ReadersBuilder::<Token<Value>, ()>::new()
+      .add_fn(|_, input, _, next| match input.read(next) {
+        Some(ch) if ch.is_whitespace() => ReaderResult::Empty,
+        _ => ReaderResult::None,
+      })
+      .add(literal("->", |meta| Token::new(meta, Value::Arrow)))
+      .add(delimited("/*", "*/", |string, meta| {
+        Token::new(meta, Value::Comment(string))
+      }))
+      .add(char_class(char::is_alphabetic, |string, meta| {
+        Token::new(meta, Value::Word(string))
+      }))
+      .build();
+
+    let tokens: Vec<Token<Value>> = readers
+      .read("a -> /* b */ cd".chars())
+      .map(Result::unwrap)
+      .collect();
+    let values: Vec<&Value> = tokens.iter().map(Token::value).collect();
+
+    assert_eq!(
+      values,
+      [
+        &Value::Word("a".into()),
+        &Value::Arrow,
+        &Value::Comment(" b ".into()),
+        &Value::Word("cd".into()),
+      ]
+    );
+    assert_eq!(tokens[2].meta().col_start(), 6);
+    assert_eq!(tokens[2].meta().col_end(), 13);
+  }
+
+  #[test]
+  fn test_unterminated_delimited() {
+    let reader = delimited("\"", "\"", |string, _| string);
+    let readers = Readers::<String, ()>::new();
+    let mut input = peek_nth::IteratorExt::peekable_nth("\"abc".chars());
+    let current = State::new();
+    let mut next = current.clone();
+
+    assert_eq!(
+      reader(&readers, &mut input, &current, &mut next),
+      ReaderResult::None
+    );
+  }
+}
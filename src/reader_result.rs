@@ -1,22 +1,14 @@
+use alloc::vec::Vec;
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum ReaderResult<T, E> {
   Some(T),
   Err(E),
   Empty,
   None,
-}
-
-unsafe impl<T, E> Send for ReaderResult<T, E>
-where
-  T: Send,
-  E: Send,
-{
-}
-unsafe impl<T, E> Sync for ReaderResult<T, E>
-where
-  T: Send,
-  E: Send,
-{
+  /// any number of tokens and errors, each with its own meta, returned in
+  /// order
+  Many(Vec<Result<T, E>>),
 }
 
 impl<T, E> From<Result<T, E>> for ReaderResult<T, E> {
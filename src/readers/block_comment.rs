@@ -0,0 +1,253 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use super::super::{Input, Reader, ReaderResult, Readers, State, TokenMeta};
+use super::{starts_with, ReadError};
+
+/// Skips everything from `open` up to and including the matching `close`,
+/// counting inner `open`s when `nested` is set. Comments starting with the
+/// `doc` marker right after `open` are returned as tokens of their text
+/// instead.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BlockCommentReader<F, G> {
+  open: String,
+  close: String,
+  nested: bool,
+  doc: Option<String>,
+  to_token: F,
+  to_error: G,
+}
+
+impl<G> BlockCommentReader<(), G> {
+  #[inline]
+  pub fn new<S>(open: S, close: S, to_error: G) -> Self
+  where
+    S: Into<String>,
+  {
+    BlockCommentReader {
+      open: open.into(),
+      close: close.into(),
+      nested: false,
+      doc: None,
+      to_token: (),
+      to_error,
+    }
+  }
+
+  /// Returns comments starting with `open` directly followed by `marker`,
+  /// like `/**` or `/*!`, as doc comments through `to_token`. A repeated
+  /// marker, as in `/***`, is not a doc comment.
+  #[inline]
+  pub fn doc<S, F>(self, marker: S, to_token: F) -> BlockCommentReader<F, G>
+  where
+    S: Into<String>,
+  {
+    BlockCommentReader {
+      open: self.open,
+      close: self.close,
+      nested: self.nested,
+      doc: Some(marker.into()),
+      to_token,
+      to_error: self.to_error,
+    }
+  }
+}
+
+impl<F, G> BlockCommentReader<F, G> {
+  /// Allow comments inside comments, each needing its own `close`.
+  #[inline]
+  pub fn nested(mut self, nested: bool) -> Self {
+    self.nested = nested;
+    self
+  }
+
+  #[inline]
+  fn is_doc(&self, input: &mut dyn Input, state: &State) -> Option<usize> {
+    let marker = self.doc.as_ref().filter(|marker| !marker.is_empty())?;
+    let mut after = state.clone();
+
+    if !starts_with(input, &after, marker) {
+      return None;
+    }
+    if starts_with(input, &after, &self.close) {
+      return None;
+    }
+
+    let len = marker.chars().count();
+    input.read_offset(&mut after, len);
+
+    if starts_with(input, &after, marker) {
+      None
+    } else {
+      Some(len)
+    }
+  }
+
+  /// Reads the comment after `open`, returning its text without the final
+  /// `close`, or `None` if it is never closed.
+  #[inline]
+  fn read_comment(&self, input: &mut dyn Input, next: &mut State) -> Option<String> {
+    let mut string = String::new();
+    let mut depth = 1;
+
+    loop {
+      if self.nested && starts_with(input, next, &self.open) {
+        input.read_offset(next, self.open.chars().count());
+        string.push_str(&self.open);
+        depth += 1;
+      } else if starts_with(input, next, &self.close) {
+        input.read_offset(next, self.close.chars().count());
+        depth -= 1;
+
+        if depth == 0 {
+          return Some(string);
+        }
+        string.push_str(&self.close);
+      } else {
+        match input.read(next) {
+          Some(ch) => string.push(ch),
+          None => return None,
+        }
+      }
+    }
+  }
+}
+
+/// How a `BlockCommentReader` turns doc comments into tokens, implemented
+/// for `()` when it has none.
+pub trait DocComment<T> {
+  fn to_token(&self, string: String, meta: TokenMeta) -> Option<T>;
+}
+
+impl<T> DocComment<T> for () {
+  #[inline(always)]
+  fn to_token(&self, _: String, _: TokenMeta) -> Option<T> {
+    None
+  }
+}
+
+impl<T, F> DocComment<T> for F
+where
+  F: Fn(String, TokenMeta) -> T,
+{
+  #[inline(always)]
+  fn to_token(&self, string: String, meta: TokenMeta) -> Option<T> {
+    Some(self(string, meta))
+  }
+}
+
+impl<T, E, F, G> Reader<T, E> for BlockCommentReader<F, G>
+where
+  F: DocComment<T>,
+  G: Fn(ReadError, TokenMeta) -> E,
+{
+  #[inline]
+  fn read(
+    &self,
+    _: &Readers<T, E>,
+    input: &mut dyn Input,
+    current: &State,
+    next: &mut State,
+  ) -> ReaderResult<T, E> {
+    if self.open.is_empty() || !starts_with(input, current, &self.open) {
+      return ReaderResult::None;
+    }
+
+    input.read_offset(next, self.open.chars().count());
+
+    let opened = next.clone();
+    let doc = self.is_doc(input, next);
+
+    match self.read_comment(input, next) {
+      Some(string) => match doc {
+        Some(len) => match self.to_token.to_token(
+          string.chars().skip(len).collect(),
+          TokenMeta::new_state_meta(current, next),
+        ) {
+          Some(token) => ReaderResult::Some(token),
+          None => ReaderResult::Empty,
+        },
+        None => ReaderResult::Empty,
+      },
+      None => ReaderResult::Err((self.to_error)(
+        ReadError::UnterminatedComment,
+        TokenMeta::new_state_meta(current, &opened),
+      )),
+    }
+  }
+
+  #[inline]
+  fn samples(&self) -> Vec<String> {
+    if self.open.is_empty() {
+      Vec::new()
+    } else {
+      vec![format!("{} a {}", self.open, self.close)]
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use alloc::vec::Vec;
+
+  type Result = core::result::Result<String, (ReadError, u64, u64)>;
+
+  fn lex<R>(string: &str, reader: R) -> Vec<Result>
+  where
+    R: 'static + Reader<Result, (ReadError, u64, u64)>,
+  {
+    super::super::lex(reader, string, |ch, _, _| Some(ch.into()))
+  }
+
+  fn to_error(error: ReadError, meta: TokenMeta) -> (ReadError, u64, u64) {
+    (error, meta.index_start(), meta.index_end())
+  }
+
+  #[test]
+  fn test_flat() {
+    let reader = BlockCommentReader::new("/*", "*/", to_error);
+
+    assert_eq!(
+      lex("a /* b /* c */ d */", reader.clone()),
+      [
+        Ok("a".into()),
+        Ok("d".into()),
+        Ok("*".into()),
+        Ok("/".into())
+      ]
+    );
+    assert_eq!(
+      lex("a /* b", reader),
+      [Ok("a".into()), Err((ReadError::UnterminatedComment, 2, 4))]
+    );
+  }
+
+  #[test]
+  fn test_nested() {
+    let reader = BlockCommentReader::new("{-", "-}", to_error).nested(true);
+
+    assert_eq!(
+      lex("a {- b {- c -} d -} e", reader.clone()),
+      [Ok("a".into()), Ok("e".into())]
+    );
+    assert_eq!(
+      lex("a {- b {- c -} d", reader),
+      [Ok("a".into()), Err((ReadError::UnterminatedComment, 2, 4))]
+    );
+  }
+
+  #[test]
+  fn test_doc() {
+    let reader = BlockCommentReader::new("/*", "*/", to_error)
+      .nested(true)
+      .doc("*", |string, _| Ok(string));
+
+    assert_eq!(
+      lex("/** a /* b */ */ /**/ /*** c */ /* d */", reader),
+      [Ok(" a /* b */ ".into())]
+    );
+  }
+}
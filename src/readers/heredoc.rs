@@ -0,0 +1,387 @@
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use super::super::{read_many, Input, Reader, ReaderResult, Readers, State, TokenMeta};
+use super::ReadError;
+
+/// Reads shell and Ruby style heredocs, `<<EOF` up to a line holding just
+/// `EOF`, returning the lines in between. The delimiter may be quoted. With
+/// `<<-` leading tabs are stripped from every line, with `<<~` the
+/// indentation common to all non blank lines; both allow the closing
+/// delimiter to be indented.
+///
+/// When the line goes on after the delimiter, as in `cat <<EOF | grep x`, the
+/// rest of it is read with the other readers and the bodies of every heredoc
+/// on the line follow its line break, in order. The heredoc tokens then span
+/// just their `<<EOF` and come back with the tokens of the line as a
+/// `ReaderResult::Many`. A body without a closing line is an
+/// `UnterminatedHeredoc` error spanning its `<<EOF`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct HeredocReader<F, G> {
+  to_token: F,
+  to_error: G,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Strip {
+  None,
+  Tabs,
+  Indentation,
+}
+
+/// A heredoc whose body starts after the line break.
+struct Pending {
+  start: State,
+  end: State,
+  strip: Strip,
+  delimiter: String,
+}
+
+impl<F, G> HeredocReader<F, G> {
+  #[inline]
+  pub fn new(to_token: F, to_error: G) -> Self {
+    HeredocReader { to_token, to_error }
+  }
+}
+
+impl<T, E, F, G> Reader<T, E> for HeredocReader<F, G>
+where
+  F: Fn(String, TokenMeta) -> T,
+  G: Fn(ReadError, TokenMeta) -> E,
+{
+  #[inline]
+  fn read(
+    &self,
+    readers: &Readers<T, E>,
+    input: &mut dyn Input,
+    current: &State,
+    next: &mut State,
+  ) -> ReaderResult<T, E> {
+    let (strip, delimiter) = match read_opener(input, next) {
+      Some(opener) => opener,
+      None => return ReaderResult::None,
+    };
+
+    if is_line_end(input, next) {
+      let opened = next.clone();
+      input.skip_line(next);
+
+      return match read_body(input, next, strip, &delimiter) {
+        Some(body) => ReaderResult::Some((self.to_token)(
+          body,
+          TokenMeta::new_state_meta(current, next),
+        )),
+        None => ReaderResult::Err((self.to_error)(
+          ReadError::UnterminatedHeredoc,
+          TokenMeta::new_state_meta(current, &opened),
+        )),
+      };
+    }
+
+    let mut pending = vec![Pending {
+      start: current.clone(),
+      end: next.clone(),
+      strip,
+      delimiter,
+    }];
+    let mut tokens = vec![None];
+
+    while !is_line_end(input, next) {
+      while let Some(' ') | Some('\t') = input.peek(next, 0) {
+        input.read(next);
+      }
+
+      let start = next.clone();
+      let mut end = next.clone();
+
+      if let Some((strip, delimiter)) = read_opener(input, &mut end) {
+        next.clone_from(&end);
+        pending.push(Pending {
+          start,
+          end,
+          strip,
+          delimiter,
+        });
+        tokens.push(None);
+      } else if !is_line_end(input, next) {
+        match read_many(readers, input, next) {
+          Some(results) => tokens.extend(results.into_iter().map(Some)),
+          None => return ReaderResult::None,
+        }
+      }
+    }
+    input.skip_line(next);
+
+    let mut heredocs = Vec::with_capacity(pending.len());
+
+    for (index, pending) in pending.into_iter().enumerate() {
+      if index != 0 {
+        input.skip_line(next);
+      }
+
+      match read_body(input, next, pending.strip, &pending.delimiter) {
+        Some(body) => heredocs.push(Ok((self.to_token)(
+          body,
+          TokenMeta::new_state_meta(&pending.start, &pending.end),
+        ))),
+        None => {
+          return ReaderResult::Err((self.to_error)(
+            ReadError::UnterminatedHeredoc,
+            TokenMeta::new_state_meta(&pending.start, &pending.end),
+          ))
+        }
+      }
+    }
+
+    let mut heredocs = heredocs.into_iter();
+
+    ReaderResult::Many(
+      tokens
+        .into_iter()
+        .map(|token| {
+          token
+            .or_else(|| heredocs.next())
+            .expect("a heredoc per opener")
+        })
+        .collect(),
+    )
+  }
+}
+
+/// Reads `<<`, `<<-` or `<<~` and a plain or quoted delimiter word.
+#[inline]
+fn read_opener(input: &mut dyn Input, next: &mut State) -> Option<(Strip, String)> {
+  if input.peek(next, 0) != Some('<') || input.peek(next, 1) != Some('<') {
+    return None;
+  }
+  input.read_offset(next, 2);
+
+  let strip = match input.peek(next, 0) {
+    Some('-') => Strip::Tabs,
+    Some('~') => Strip::Indentation,
+    _ => Strip::None,
+  };
+  if strip != Strip::None {
+    input.read(next);
+  }
+
+  let quote = match input.peek(next, 0) {
+    Some(ch @ '\'') | Some(ch @ '"') => {
+      input.read(next);
+      Some(ch)
+    }
+    _ => None,
+  };
+  let mut delimiter = String::new();
+
+  while let Some(ch) = input.peek(next, 0) {
+    if ch.is_alphanumeric() || ch == '_' {
+      input.read(next);
+      delimiter.push(ch);
+    } else {
+      break;
+    }
+  }
+
+  if delimiter.is_empty() || delimiter.starts_with(|ch: char| ch.is_ascii_digit()) {
+    return None;
+  }
+  if let Some(quote) = quote {
+    if input.read(next) != Some(quote) {
+      return None;
+    }
+  }
+
+  Some((strip, delimiter))
+}
+
+/// Whether only blanks are left before the line break or the end of input.
+#[inline]
+fn is_line_end(input: &mut dyn Input, next: &State) -> bool {
+  let mut offset = 0;
+
+  while let Some(' ') | Some('\t') = input.peek(next, offset) {
+    offset += 1;
+  }
+
+  match input.peek(next, offset) {
+    Some(ch) => next.is_line_break(ch),
+    None => true,
+  }
+}
+
+/// Reads the lines of a body up to and including its closing delimiter line,
+/// or returns `None` if the input ends first.
+#[inline]
+fn read_body(
+  input: &mut dyn Input,
+  next: &mut State,
+  strip: Strip,
+  delimiter: &str,
+) -> Option<String> {
+  let mut lines = Vec::new();
+
+  loop {
+    let line = input.peek_line(next)?;
+    let trimmed = match strip {
+      Strip::None => line.as_str(),
+      Strip::Tabs => line.trim_start_matches('\t'),
+      Strip::Indentation => line.trim_start_matches([' ', '\t']),
+    };
+
+    if trimmed == delimiter {
+      input.read_offset(next, line.chars().count());
+      break;
+    }
+
+    lines.push(input.read_line(next).expect("peeked line"));
+  }
+
+  let indentation = if strip == Strip::Indentation {
+    lines
+      .iter()
+      .filter(|line| !line.trim().is_empty())
+      .map(|line| line.len() - line.trim_start_matches([' ', '\t']).len())
+      .min()
+      .unwrap_or(0)
+  } else {
+    0
+  };
+
+  let mut body = String::new();
+
+  for line in &lines {
+    let line = match strip {
+      Strip::None => line.as_str(),
+      Strip::Tabs => line.trim_start_matches('\t'),
+      Strip::Indentation => line.get(indentation.min(line.len())..).unwrap_or(""),
+    };
+    body.push_str(line);
+    body.push('\n');
+  }
+
+  Some(body)
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  type Result = core::result::Result<(String, u64, u64), (ReadError, u64, u64)>;
+
+  fn lex(string: &str) -> Vec<Result> {
+    let reader = HeredocReader::new(
+      |body, meta: TokenMeta| Ok((body, meta.index_start(), meta.index_end())),
+      |error, meta: TokenMeta| (error, meta.index_start(), meta.index_end()),
+    );
+
+    super::super::lex(reader, string, |ch, current, next| {
+      Some((ch.into(), current.index() as u64, next.index() as u64))
+    })
+  }
+
+  #[test]
+  fn test_heredoc() {
+    assert_eq!(
+      lex("<<EOF\n  a\n EOF\nEOF\n;"),
+      [Ok(("  a\n EOF\n".into(), 0, 18)), Ok((";".into(), 19, 20))]
+    );
+    assert_eq!(lex("<<'END_1'  \nb\nEND_1"), [Ok(("b\n".into(), 0, 19))]);
+  }
+
+  #[test]
+  fn test_strip() {
+    assert_eq!(
+      lex("<<-EOF\n\t\ta\n\t b\n\tEOF"),
+      [Ok(("a\n b\n".into(), 0, 19))]
+    );
+    assert_eq!(
+      lex("<<~EOF\n    a\n\n      b\n  EOF"),
+      [Ok(("a\n\n  b\n".into(), 0, 27))]
+    );
+  }
+
+  #[test]
+  fn test_line_goes_on() {
+    assert_eq!(
+      lex("cat <<EOF | grep x\na\nEOF\n;"),
+      [
+        Ok(("c".into(), 0, 1)),
+        Ok(("a".into(), 1, 2)),
+        Ok(("t".into(), 2, 3)),
+        Ok(("a\n".into(), 4, 9)),
+        Ok(("|".into(), 10, 11)),
+        Ok(("g".into(), 12, 13)),
+        Ok(("r".into(), 13, 14)),
+        Ok(("e".into(), 14, 15)),
+        Ok(("p".into(), 15, 16)),
+        Ok(("x".into(), 17, 18)),
+        Ok((";".into(), 25, 26)),
+      ]
+    );
+    assert_eq!(
+      lex("(<<~EOF, x)\n  a\n  EOF\n"),
+      [
+        Ok(("(".into(), 0, 1)),
+        Ok(("a\n".into(), 1, 7)),
+        Ok((",".into(), 7, 8)),
+        Ok(("x".into(), 9, 10)),
+        Ok((")".into(), 10, 11)),
+      ]
+    );
+  }
+
+  #[test]
+  fn test_several_on_a_line() {
+    assert_eq!(
+      lex("<<A <<-B\n1\nA\n\t2\n\tB"),
+      [Ok(("1\n".into(), 0, 3)), Ok(("2\n".into(), 4, 8))]
+    );
+  }
+
+  #[test]
+  fn test_not_heredoc() {
+    assert_eq!(
+      lex("a<<1 c"),
+      [
+        Ok(("a".into(), 0, 1)),
+        Ok(("<".into(), 1, 2)),
+        Ok(("<".into(), 2, 3)),
+        Ok(("1".into(), 3, 4)),
+        Ok(("c".into(), 5, 6)),
+      ]
+    );
+  }
+
+  #[test]
+  fn test_unterminated() {
+    assert_eq!(
+      lex("x <<~EOF\na\n EOFX"),
+      [
+        Ok(("x".into(), 0, 1)),
+        Err((ReadError::UnterminatedHeredoc, 2, 8))
+      ]
+    );
+    assert_eq!(
+      lex(
+        "<<A <<B c
+A
+b"
+      ),
+      [Err((ReadError::UnterminatedHeredoc, 4, 7))]
+    );
+  }
+
+  #[test]
+  fn test_strip_tabs_only() {
+    assert_eq!(
+      lex(
+        "<<-EOF
+  EOF
+	EOF"
+      ),
+      [Ok(("  EOF\n".into(), 0, 17))]
+    );
+  }
+}
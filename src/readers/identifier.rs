@@ -0,0 +1,145 @@
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use super::super::{Input, Reader, ReaderResult, Readers, State, TokenMeta};
+
+/// Reads a char matching `is_start` followed by chars matching
+/// `is_continue`, by default letters, digits and `_` not starting with a
+/// digit.
+#[derive(Debug, Clone, Copy)]
+pub struct IdentifierReader<F> {
+  is_start: fn(char) -> bool,
+  is_continue: fn(char) -> bool,
+  to_token: F,
+}
+
+impl<F> IdentifierReader<F> {
+  #[inline]
+  pub fn new(to_token: F) -> Self {
+    IdentifierReader {
+      is_start: is_identifier_start,
+      is_continue: is_identifier_continue,
+      to_token,
+    }
+  }
+
+  #[inline]
+  pub fn with_start(mut self, is_start: fn(char) -> bool) -> Self {
+    self.is_start = is_start;
+    self
+  }
+
+  #[inline]
+  pub fn with_continue(mut self, is_continue: fn(char) -> bool) -> Self {
+    self.is_continue = is_continue;
+    self
+  }
+}
+
+impl<T, E, F> Reader<T, E> for IdentifierReader<F>
+where
+  F: Fn(String, TokenMeta) -> T,
+{
+  #[inline]
+  fn read(
+    &self,
+    _: &Readers<T, E>,
+    input: &mut dyn Input,
+    current: &State,
+    next: &mut State,
+  ) -> ReaderResult<T, E> {
+    match input.peek(next, 0) {
+      Some(ch) if (self.is_start)(ch) => {
+        let mut string = String::new();
+
+        input.read(next);
+        string.push(ch);
+
+        while let Some(ch) = input.peek(next, 0) {
+          if (self.is_continue)(ch) {
+            input.read(next);
+            string.push(ch);
+          } else {
+            break;
+          }
+        }
+
+        ReaderResult::Some((self.to_token)(
+          string,
+          TokenMeta::new_state_meta(current, next),
+        ))
+      }
+      _ => ReaderResult::None,
+    }
+  }
+
+  #[inline]
+  fn samples(&self) -> Vec<String> {
+    ["a", "_", "A"]
+      .iter()
+      .find(|sample| sample.chars().all(self.is_start))
+      .map(|&sample| vec![sample.into()])
+      .unwrap_or_default()
+  }
+}
+
+#[inline]
+fn is_identifier_start(ch: char) -> bool {
+  ch.is_alphabetic() || ch == '_'
+}
+
+#[inline]
+fn is_identifier_continue(ch: char) -> bool {
+  ch.is_alphanumeric() || ch == '_'
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  type Token = (String, u64, u64);
+  type ToToken = fn(String, TokenMeta) -> Result<Token, ()>;
+
+  fn lex(reader: IdentifierReader<ToToken>, string: &str) -> Vec<Token> {
+    super::super::lex(reader, string, |ch, current, next| {
+      Some((ch.into(), current.index() as u64, next.index() as u64))
+    })
+    .into_iter()
+    .map(Result::unwrap)
+    .collect()
+  }
+
+  fn to_token(string: String, meta: TokenMeta) -> Result<Token, ()> {
+    Ok((string, meta.index_start(), meta.index_end()))
+  }
+
+  #[test]
+  fn test_identifier() {
+    assert_eq!(
+      lex(IdentifierReader::new(to_token as ToToken), "_a1 1b éß"),
+      [
+        ("_a1".into(), 0, 3),
+        ("1".into(), 4, 5),
+        ("b".into(), 5, 6),
+        ("éß".into(), 7, 9)
+      ]
+    );
+  }
+
+  #[test]
+  fn test_start_and_continue() {
+    let reader = IdentifierReader::new(to_token as ToToken)
+      .with_start(|ch| ch == '$')
+      .with_continue(|ch| ch.is_ascii_lowercase() || ch == '-');
+
+    assert_eq!(
+      lex(reader, "$a-b a"),
+      [("$a-b".into(), 0, 4), ("a".into(), 5, 6)]
+    );
+    assert_eq!(
+      Reader::<Result<Token, ()>, ()>::samples(&reader),
+      Vec::<String>::new()
+    );
+  }
+}
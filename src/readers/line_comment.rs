@@ -0,0 +1,91 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use super::super::{Input, Reader, ReaderResult, Readers, State};
+use super::starts_with;
+
+/// Skips from `prefix` up to, but not including, the end of the line.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LineCommentReader {
+  prefix: String,
+}
+
+impl LineCommentReader {
+  #[inline]
+  pub fn new<S>(prefix: S) -> Self
+  where
+    S: Into<String>,
+  {
+    LineCommentReader {
+      prefix: prefix.into(),
+    }
+  }
+}
+
+impl<T, E> Reader<T, E> for LineCommentReader {
+  #[inline]
+  fn read(
+    &self,
+    _: &Readers<T, E>,
+    input: &mut dyn Input,
+    current: &State,
+    next: &mut State,
+  ) -> ReaderResult<T, E> {
+    if self.prefix.is_empty() || !starts_with(input, current, &self.prefix) {
+      return ReaderResult::None;
+    }
+
+    while let Some(ch) = input.peek(next, 0) {
+      if next.is_line_break(ch) {
+        break;
+      }
+      input.read(next);
+    }
+
+    ReaderResult::Empty
+  }
+
+  #[inline]
+  fn samples(&self) -> Vec<String> {
+    if self.prefix.is_empty() {
+      Vec::new()
+    } else {
+      vec![format!("{} a", self.prefix)]
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::super::super::ReadersBuilder;
+  use super::super::lex_with;
+  use super::*;
+
+  fn lex(prefix: &str, string: &str) -> Vec<(char, usize)> {
+    lex_with(
+      ReadersBuilder::new().add(LineCommentReader::new(prefix)),
+      string,
+      |ch, current, _| Some((ch, current.index())),
+    )
+    .into_iter()
+    .map(|result: Result<_, ()>| result.unwrap())
+    .collect()
+  }
+
+  #[test]
+  fn test_line_comment() {
+    assert_eq!(lex("//", "a// b\nc//"), [('a', 0), ('\n', 5), ('c', 6)]);
+  }
+
+  #[test]
+  fn test_prefix() {
+    assert_eq!(lex("#", "/ #/\n#"), [('/', 0), (' ', 1), ('\n', 4)]);
+    assert_eq!(lex("", "a\n"), [('a', 0), ('\n', 1)]);
+    assert_eq!(
+      Reader::<(), ()>::samples(&LineCommentReader::new(";")),
+      ["; a"]
+    );
+  }
+}
@@ -0,0 +1,78 @@
+use super::input::starts_with;
+#[cfg(test)]
+use super::{ReaderResult, ReadersBuilder, State};
+#[cfg(test)]
+use alloc::vec::Vec;
+
+mod block_comment;
+mod heredoc;
+mod identifier;
+mod line_comment;
+mod number;
+mod number_literal;
+mod raw_string;
+mod read_error;
+mod string;
+mod whitespace;
+
+pub use self::block_comment::{BlockCommentReader, DocComment};
+pub use self::heredoc::HeredocReader;
+pub use self::identifier::IdentifierReader;
+pub use self::line_comment::LineCommentReader;
+pub use self::number::{Number, NumberReader};
+pub use self::number_literal::{NumberLiteral, NumberSuffix};
+pub use self::raw_string::RawStringReader;
+pub use self::read_error::ReadError;
+pub use self::string::StringReader;
+pub use self::whitespace::WhitespaceReader;
+
+/// Reads `string` with `WhitespaceReader` and `reader`, see `lex_with`.
+#[cfg(test)]
+pub(crate) fn lex<T, E, R, F>(reader: R, string: &str, to_token: F) -> Vec<Result<T, E>>
+where
+  T: 'static,
+  E: 'static,
+  R: 'static + super::Reader<Result<T, E>, E>,
+  F: 'static + Fn(char, &State, &State) -> Option<T>,
+{
+  lex_with(
+    ReadersBuilder::new()
+      .add(WhitespaceReader::new())
+      .add(reader),
+    string,
+    to_token,
+  )
+}
+
+/// Reads `string` with `readers`, then turns any other char into a token
+/// with `to_token`, or skips it for `None`. Readers return their tokens as
+/// `Result`s, so errors are flattened in with them.
+#[cfg(test)]
+pub(crate) fn lex_with<T, E, F>(
+  readers: ReadersBuilder<Result<T, E>, E>,
+  string: &str,
+  to_token: F,
+) -> Vec<Result<T, E>>
+where
+  T: 'static,
+  E: 'static,
+  F: 'static + Fn(char, &State, &State) -> Option<T>,
+{
+  let readers = readers
+    .add_fn(move |_, input, current, next| match input.read(next) {
+      Some(ch) => match to_token(ch, current, next) {
+        Some(token) => ReaderResult::Some(Ok(token)),
+        None => ReaderResult::Empty,
+      },
+      None => ReaderResult::None,
+    })
+    .build();
+
+  readers
+    .read(string.chars())
+    .map(|result| match result {
+      Ok(token) => token,
+      Err(error) => Err(error),
+    })
+    .collect()
+}
@@ -0,0 +1,467 @@
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use super::super::{Input, Reader, ReaderResult, Readers, State, TokenMeta};
+use super::{NumberLiteral, NumberSuffix, ReadError};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, PartialOrd)]
+pub enum Number {
+  Integer(i64),
+  Float(f64),
+  /// An integer too large for `i64`, as its sign and digits in the literal's
+  /// radix without separators.
+  BigInteger(String),
+}
+
+/// Reads decimal, `0x` hex, `0o` octal and `0b` binary integers and decimal
+/// floats with fractions and exponents, all allowing `_` between digits.
+/// Letters running into a number make it malformed unless they are a type
+/// suffix and `suffixes` is set.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct NumberReader<F, G> {
+  signed: bool,
+  floats: bool,
+  suffixes: bool,
+  big_integers: bool,
+  to_token: F,
+  to_error: G,
+}
+
+impl<F, G> NumberReader<F, G> {
+  #[inline]
+  pub fn new(to_token: F, to_error: G) -> Self {
+    NumberReader {
+      signed: false,
+      floats: true,
+      suffixes: false,
+      big_integers: false,
+      to_token,
+      to_error,
+    }
+  }
+
+  /// Also read a leading `+` or `-` directly followed by a digit.
+  #[inline]
+  pub fn signed(mut self, signed: bool) -> Self {
+    self.signed = signed;
+    self
+  }
+
+  /// Read fractions and exponents, on by default.
+  #[inline]
+  pub fn floats(mut self, floats: bool) -> Self {
+    self.floats = floats;
+    self
+  }
+
+  /// Read Rust style type suffixes like `u8` or `f32`, optionally after a
+  /// `_`, and check the value fits the type.
+  #[inline]
+  pub fn suffixes(mut self, suffixes: bool) -> Self {
+    self.suffixes = suffixes;
+    self
+  }
+
+  /// Return unsuffixed integers too large for `i64` as
+  /// `Number::BigInteger` instead of reporting an overflow.
+  #[inline]
+  pub fn big_integers(mut self, big_integers: bool) -> Self {
+    self.big_integers = big_integers;
+    self
+  }
+
+  #[inline]
+  fn read_suffix(
+    &self,
+    input: &mut dyn Input,
+    next: &mut State,
+  ) -> Result<Option<NumberSuffix>, ReadError> {
+    let mut string = String::new();
+
+    while let Some(ch) = input.peek(next, 0) {
+      if ch.is_alphanumeric() || ch == '_' {
+        input.read(next);
+        string.push(ch);
+      } else {
+        break;
+      }
+    }
+
+    if string.is_empty() {
+      Ok(None)
+    } else if self.suffixes {
+      match string.trim_start_matches('_').parse::<NumberSuffix>() {
+        Ok(suffix) if self.floats || !suffix.is_float() => Ok(Some(suffix)),
+        _ => Err(ReadError::MalformedNumber),
+      }
+    } else {
+      Err(ReadError::MalformedNumber)
+    }
+  }
+
+  #[inline]
+  fn to_integer(
+    &self,
+    negative: bool,
+    digits: String,
+    radix: u32,
+    suffix: Option<NumberSuffix>,
+  ) -> Result<Number, ReadError> {
+    let magnitude = u128::from_str_radix(&digits, radix).ok();
+
+    if let (Some(magnitude), Some(suffix)) = (magnitude, suffix) {
+      if magnitude > suffix.max_magnitude(negative) {
+        return Err(ReadError::NumberOverflow);
+      }
+    }
+
+    match magnitude {
+      Some(magnitude) if negative && magnitude <= 1 << 63 => {
+        Ok(Number::Integer(-(magnitude as i128) as i64))
+      }
+      Some(magnitude) if !negative && magnitude <= i64::MAX as u128 => {
+        Ok(Number::Integer(magnitude as i64))
+      }
+      Some(_) if suffix.is_some() || self.big_integers => Ok(big_integer(negative, digits)),
+      None if suffix.is_none() && self.big_integers => Ok(big_integer(negative, digits)),
+      _ => Err(ReadError::NumberOverflow),
+    }
+  }
+}
+
+impl<T, E, F, G> Reader<T, E> for NumberReader<F, G>
+where
+  F: Fn(NumberLiteral, TokenMeta) -> T,
+  G: Fn(ReadError, TokenMeta) -> E,
+{
+  #[inline]
+  fn read(
+    &self,
+    _: &Readers<T, E>,
+    input: &mut dyn Input,
+    current: &State,
+    next: &mut State,
+  ) -> ReaderResult<T, E> {
+    let mut string = String::new();
+    let mut negative = false;
+
+    if self.signed && is_sign(input.peek(next, 0)) && is_digit(input.peek(next, 1), 10) {
+      negative = input.read(next) == Some('-');
+    }
+    if !is_digit(input.peek(next, 0), 10) {
+      return ReaderResult::None;
+    }
+
+    let radix = match (input.peek(next, 0), input.peek(next, 1)) {
+      (Some('0'), Some('x')) | (Some('0'), Some('X')) => 16,
+      (Some('0'), Some('o')) | (Some('0'), Some('O')) => 8,
+      (Some('0'), Some('b')) | (Some('0'), Some('B')) => 2,
+      _ => 10,
+    };
+    let mut is_float = false;
+    let mut is_malformed = false;
+
+    if radix != 10 {
+      input.read_offset(next, 2);
+      is_malformed = read_digits(input, next, radix, &mut string) == 0;
+    } else {
+      read_digits(input, next, 10, &mut string);
+
+      if self.floats && input.peek(next, 0) == Some('.') && is_digit(input.peek(next, 1), 10) {
+        input.read(next);
+        string.push('.');
+        read_digits(input, next, 10, &mut string);
+        is_float = true;
+      }
+      if self.floats && is_exponent(input, next) {
+        input.read(next);
+        string.push('e');
+        if is_sign(input.peek(next, 0)) {
+          string.extend(input.read(next));
+        }
+        read_digits(input, next, 10, &mut string);
+        is_float = true;
+      }
+    }
+
+    let suffix = self.read_suffix(input, next);
+    let meta = TokenMeta::new_state_meta(current, next);
+
+    let suffix = match suffix {
+      Ok(suffix) if !is_malformed => suffix,
+      _ => return ReaderResult::Err((self.to_error)(ReadError::MalformedNumber, meta)),
+    };
+    let is_float_suffix = suffix.is_some_and(|suffix| suffix.is_float());
+
+    let number = if is_float || is_float_suffix {
+      if suffix.is_some_and(|suffix| !suffix.is_float()) || radix != 10 {
+        Err(ReadError::MalformedNumber)
+      } else {
+        parse_float(negative, &string, suffix)
+      }
+    } else {
+      self.to_integer(negative, string, radix, suffix)
+    };
+
+    match number {
+      Ok(number) => ReaderResult::Some((self.to_token)(
+        NumberLiteral::new(number, radix, suffix),
+        meta,
+      )),
+      Err(error) => ReaderResult::Err((self.to_error)(error, meta)),
+    }
+  }
+
+  #[inline]
+  fn samples(&self) -> Vec<String> {
+    vec!["1".into()]
+  }
+}
+
+#[inline]
+fn big_integer(negative: bool, digits: String) -> Number {
+  if negative {
+    let mut string = String::from("-");
+    string.push_str(&digits);
+    Number::BigInteger(string)
+  } else {
+    Number::BigInteger(digits)
+  }
+}
+
+#[inline]
+fn parse_float(
+  negative: bool,
+  string: &str,
+  suffix: Option<NumberSuffix>,
+) -> Result<Number, ReadError> {
+  let float = if suffix == Some(NumberSuffix::F32) {
+    string.parse::<f32>().map(f64::from)
+  } else {
+    string.parse::<f64>()
+  };
+
+  match float {
+    Ok(float) if float.is_finite() => Ok(Number::Float(if negative { -float } else { float })),
+    Ok(_) => Err(ReadError::NumberOverflow),
+    Err(_) => Err(ReadError::MalformedNumber),
+  }
+}
+
+#[inline]
+fn is_sign(ch: Option<char>) -> bool {
+  ch == Some('-') || ch == Some('+')
+}
+
+#[inline]
+fn is_digit(ch: Option<char>, radix: u32) -> bool {
+  ch.is_some_and(|ch| ch.is_digit(radix))
+}
+
+#[inline]
+fn is_exponent(input: &mut dyn Input, state: &State) -> bool {
+  match input.peek(state, 0) {
+    Some('e') | Some('E') => {
+      is_digit(input.peek(state, 1), 10)
+        || (is_sign(input.peek(state, 1)) && is_digit(input.peek(state, 2), 10))
+    }
+    _ => false,
+  }
+}
+
+#[inline]
+fn read_digits(input: &mut dyn Input, state: &mut State, radix: u32, string: &mut String) -> usize {
+  let mut count = 0;
+
+  while let Some(ch) = input.peek(state, 0) {
+    if ch.is_digit(radix) {
+      count += 1;
+      string.push(ch);
+    } else if ch != '_' {
+      break;
+    }
+    input.read(state);
+  }
+
+  count
+}
+
+#[cfg(test)]
+mod test {
+  use super::super::super::ReadersBuilder;
+  use super::*;
+  use alloc::vec::Vec;
+
+  type Result = core::result::Result<(Number, u64), (ReadError, u64, u64)>;
+
+  type ToToken = fn(NumberLiteral, TokenMeta) -> Result;
+  type ToError = fn(ReadError, TokenMeta) -> (ReadError, u64, u64);
+
+  fn reader() -> NumberReader<ToToken, ToError> {
+    NumberReader::new(
+      |literal, meta| Ok((literal.into_value(), meta.len())),
+      |error, meta| (error, meta.index_start(), meta.index_end()),
+    )
+  }
+
+  fn lex(string: &str, signed: bool, floats: bool) -> Vec<Result> {
+    lex_with(string, reader().signed(signed).floats(floats))
+  }
+
+  fn lex_with(string: &str, reader: NumberReader<ToToken, ToError>) -> Vec<Result> {
+    super::super::lex(reader, string, |_, _, _| None)
+  }
+
+  #[test]
+  fn test_integers() {
+    assert_eq!(
+      lex("0 42 1_000 0xff 0o17 0b1010 0X_F", false, true),
+      [
+        Ok((Number::Integer(0), 1)),
+        Ok((Number::Integer(42), 2)),
+        Ok((Number::Integer(1000), 5)),
+        Ok((Number::Integer(255), 4)),
+        Ok((Number::Integer(15), 4)),
+        Ok((Number::Integer(10), 6)),
+        Ok((Number::Integer(15), 4)),
+      ]
+    );
+  }
+
+  #[test]
+  fn test_floats() {
+    assert_eq!(
+      lex("1.5 2e3 1_0.2_5E-1 3.", false, true),
+      [
+        Ok((Number::Float(1.5), 3)),
+        Ok((Number::Float(2000.0), 3)),
+        Ok((Number::Float(1.025), 10)),
+        Ok((Number::Integer(3), 1)),
+      ]
+    );
+    assert_eq!(
+      lex("1.5", false, false),
+      [Ok((Number::Integer(1), 1)), Ok((Number::Integer(5), 1))]
+    );
+  }
+
+  #[test]
+  fn test_signed() {
+    assert_eq!(
+      lex("-12 +3", true, true),
+      [Ok((Number::Integer(-12), 3)), Ok((Number::Integer(3), 2))]
+    );
+    assert_eq!(
+      lex("-9223372036854775808", true, true),
+      [Ok((Number::Integer(i64::MIN), 20))]
+    );
+  }
+
+  #[test]
+  fn test_errors() {
+    assert_eq!(
+      lex("0x 12ab 0b12", false, true),
+      [
+        Err((ReadError::MalformedNumber, 0, 2)),
+        Err((ReadError::MalformedNumber, 3, 7)),
+        Err((ReadError::MalformedNumber, 8, 12)),
+      ]
+    );
+    assert_eq!(
+      lex("9223372036854775808 1e999", false, true),
+      [
+        Err((ReadError::NumberOverflow, 0, 19)),
+        Err((ReadError::NumberOverflow, 20, 25)),
+      ]
+    );
+  }
+
+  #[test]
+  fn test_lone_sign() {
+    assert_eq!(lex("- +", true, true), []);
+    assert_eq!(lex("-x", true, true), []);
+  }
+
+  #[test]
+  fn test_suffixes() {
+    let reader = reader().signed(true).suffixes(true);
+
+    assert_eq!(
+      lex_with("255u8 -128i8 0xff_u16 1f32 2.5_f64 0x1f32 1e2f32", reader),
+      [
+        Ok((Number::Integer(255), 5)),
+        Ok((Number::Integer(-128), 6)),
+        Ok((Number::Integer(255), 8)),
+        Ok((Number::Float(1.0), 4)),
+        Ok((Number::Float(2.5), 7)),
+        Ok((Number::Integer(0x1f32), 6)),
+        Ok((Number::Float(100.0), 6)),
+      ]
+    );
+    assert_eq!(
+      lex_with("256u8 -129i8 -1u32 1e39f32 1.5u8 0b1f32 1abc", reader),
+      [
+        Err((ReadError::NumberOverflow, 0, 5)),
+        Err((ReadError::NumberOverflow, 6, 12)),
+        Err((ReadError::NumberOverflow, 13, 18)),
+        Err((ReadError::NumberOverflow, 19, 26)),
+        Err((ReadError::MalformedNumber, 27, 32)),
+        Err((ReadError::MalformedNumber, 33, 39)),
+        Err((ReadError::MalformedNumber, 40, 44)),
+      ]
+    );
+  }
+
+  #[test]
+  fn test_literal() {
+    let readers = ReadersBuilder::new()
+      .add(NumberReader::new(|literal, _| literal, |error: ReadError, _| error).suffixes(true))
+      .build();
+
+    assert_eq!(
+      readers.read("0o17u64".chars()).collect::<Vec<_>>(),
+      [Ok(NumberLiteral::new(
+        Number::Integer(15),
+        8,
+        Some(NumberSuffix::U64)
+      ))]
+    );
+  }
+
+  #[test]
+  fn test_big_integers() {
+    let reader = reader().signed(true).suffixes(true);
+
+    assert_eq!(
+      lex_with(
+        "18446744073709551615u64 -170141183460469231731687303715884105728i128",
+        reader
+      ),
+      [
+        Ok((Number::BigInteger("18446744073709551615".into()), 23)),
+        Ok((
+          Number::BigInteger("-170141183460469231731687303715884105728".into()),
+          44
+        )),
+      ]
+    );
+    assert_eq!(
+      lex_with(
+        "-0x1_0000_0000_0000_0000 99999999999999999999999999999999999999999",
+        reader.big_integers(true)
+      ),
+      [
+        Ok((Number::BigInteger("-10000000000000000".into()), 24)),
+        Ok((
+          Number::BigInteger("99999999999999999999999999999999999999999".into()),
+          41
+        )),
+      ]
+    );
+    assert_eq!(
+      lex_with("99999999999999999999999999999999999999999u128", reader),
+      [Err((ReadError::NumberOverflow, 0, 45))]
+    );
+  }
+}
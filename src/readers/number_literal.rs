@@ -0,0 +1,179 @@
+use core::fmt;
+use core::str::FromStr;
+
+use super::{Number, ReadError};
+
+/// Type suffixes `NumberReader` accepts with `suffixes` set, as in `255u8` or
+/// `1.5f32`.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum NumberSuffix {
+  I8,
+  I16,
+  I32,
+  I64,
+  I128,
+  Isize,
+  U8,
+  U16,
+  U32,
+  U64,
+  U128,
+  Usize,
+  F32,
+  F64,
+}
+
+impl NumberSuffix {
+  const ALL: [NumberSuffix; 14] = [
+    NumberSuffix::I8,
+    NumberSuffix::I16,
+    NumberSuffix::I32,
+    NumberSuffix::I64,
+    NumberSuffix::I128,
+    NumberSuffix::Isize,
+    NumberSuffix::U8,
+    NumberSuffix::U16,
+    NumberSuffix::U32,
+    NumberSuffix::U64,
+    NumberSuffix::U128,
+    NumberSuffix::Usize,
+    NumberSuffix::F32,
+    NumberSuffix::F64,
+  ];
+
+  #[inline]
+  pub fn as_str(&self) -> &'static str {
+    match *self {
+      NumberSuffix::I8 => "i8",
+      NumberSuffix::I16 => "i16",
+      NumberSuffix::I32 => "i32",
+      NumberSuffix::I64 => "i64",
+      NumberSuffix::I128 => "i128",
+      NumberSuffix::Isize => "isize",
+      NumberSuffix::U8 => "u8",
+      NumberSuffix::U16 => "u16",
+      NumberSuffix::U32 => "u32",
+      NumberSuffix::U64 => "u64",
+      NumberSuffix::U128 => "u128",
+      NumberSuffix::Usize => "usize",
+      NumberSuffix::F32 => "f32",
+      NumberSuffix::F64 => "f64",
+    }
+  }
+
+  #[inline]
+  pub fn is_float(&self) -> bool {
+    matches!(*self, NumberSuffix::F32 | NumberSuffix::F64)
+  }
+
+  #[inline]
+  pub fn is_signed(&self) -> bool {
+    matches!(
+      *self,
+      NumberSuffix::I8
+        | NumberSuffix::I16
+        | NumberSuffix::I32
+        | NumberSuffix::I64
+        | NumberSuffix::I128
+        | NumberSuffix::Isize
+    )
+  }
+
+  /// Size in bits, `isize` and `usize` counting as 64.
+  #[inline]
+  pub fn bits(&self) -> u32 {
+    match *self {
+      NumberSuffix::I8 | NumberSuffix::U8 => 8,
+      NumberSuffix::I16 | NumberSuffix::U16 => 16,
+      NumberSuffix::I32 | NumberSuffix::U32 | NumberSuffix::F32 => 32,
+      NumberSuffix::I64
+      | NumberSuffix::U64
+      | NumberSuffix::Isize
+      | NumberSuffix::Usize
+      | NumberSuffix::F64 => 64,
+      NumberSuffix::I128 | NumberSuffix::U128 => 128,
+    }
+  }
+
+  /// The largest magnitude an integer of this type can hold with the given
+  /// sign.
+  #[inline]
+  pub(crate) fn max_magnitude(&self, negative: bool) -> u128 {
+    let bits = self.bits();
+
+    match (self.is_signed(), negative) {
+      (true, true) => 1 << (bits - 1),
+      (true, false) => (1 << (bits - 1)) - 1,
+      (false, true) => 0,
+      (false, false) => u128::MAX >> (128 - bits),
+    }
+  }
+}
+
+impl FromStr for NumberSuffix {
+  type Err = ReadError;
+
+  #[inline]
+  fn from_str(string: &str) -> Result<Self, Self::Err> {
+    Self::ALL
+      .iter()
+      .find(|suffix| suffix.as_str() == string)
+      .cloned()
+      .ok_or(ReadError::MalformedNumber)
+  }
+}
+
+impl fmt::Display for NumberSuffix {
+  #[inline]
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    f.write_str(self.as_str())
+  }
+}
+
+/// A number read by `NumberReader` with the radix it was written in and its
+/// type suffix, if any.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, PartialOrd)]
+pub struct NumberLiteral {
+  value: Number,
+  radix: u32,
+  suffix: Option<NumberSuffix>,
+}
+
+impl NumberLiteral {
+  #[inline]
+  pub fn new(value: Number, radix: u32, suffix: Option<NumberSuffix>) -> Self {
+    NumberLiteral {
+      value,
+      radix,
+      suffix,
+    }
+  }
+
+  #[inline(always)]
+  pub fn value(&self) -> &Number {
+    &self.value
+  }
+  #[inline(always)]
+  pub fn into_value(self) -> Number {
+    self.value
+  }
+
+  #[inline(always)]
+  pub fn radix(&self) -> u32 {
+    self.radix
+  }
+
+  #[inline(always)]
+  pub fn suffix(&self) -> Option<NumberSuffix> {
+    self.suffix
+  }
+
+  #[inline]
+  pub fn is_negative(&self) -> bool {
+    match self.value {
+      Number::Integer(n) => n < 0,
+      Number::Float(n) => n.is_sign_negative(),
+      Number::BigInteger(ref n) => n.starts_with('-'),
+    }
+  }
+}
@@ -0,0 +1,173 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use super::super::{Input, Reader, ReaderResult, Readers, State, TokenMeta};
+use super::{starts_with, ReadError};
+
+/// Reads C++ style raw strings, `R"delim(...)delim"`, returning the text
+/// between the parentheses as is. The delimiter is up to 16 chars other than
+/// spaces, parentheses, backslashes and line breaks.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RawStringReader<F, G> {
+  prefix: String,
+  to_token: F,
+  to_error: G,
+}
+
+impl<F, G> RawStringReader<F, G> {
+  #[inline]
+  pub fn new(to_token: F, to_error: G) -> Self {
+    RawStringReader {
+      prefix: "R".into(),
+      to_token,
+      to_error,
+    }
+  }
+
+  /// The prefix before the opening `"`, `R` by default.
+  #[inline]
+  pub fn prefix<S>(mut self, prefix: S) -> Self
+  where
+    S: Into<String>,
+  {
+    self.prefix = prefix.into();
+    self
+  }
+}
+
+impl<T, E, F, G> Reader<T, E> for RawStringReader<F, G>
+where
+  F: Fn(String, TokenMeta) -> T,
+  G: Fn(ReadError, TokenMeta) -> E,
+{
+  #[inline]
+  fn read(
+    &self,
+    _: &Readers<T, E>,
+    input: &mut dyn Input,
+    current: &State,
+    next: &mut State,
+  ) -> ReaderResult<T, E> {
+    let prefix_len = self.prefix.chars().count();
+
+    if !starts_with(input, next, &self.prefix) || input.peek(next, prefix_len) != Some('"') {
+      return ReaderResult::None;
+    }
+
+    let mut close = String::from(")");
+    let mut offset = prefix_len + 1;
+
+    loop {
+      match input.peek(next, offset) {
+        Some('(') => break,
+        Some(ch)
+          if offset - prefix_len <= 16
+            && !matches!(ch, ' ' | ')' | '\\' | '"')
+            && !next.is_line_break(ch) =>
+        {
+          close.push(ch);
+          offset += 1;
+        }
+        _ => return ReaderResult::None,
+      }
+    }
+    close.push('"');
+
+    input.read_offset(next, offset + 1);
+
+    let opened = next.clone();
+    let mut string = String::new();
+
+    loop {
+      if starts_with(input, next, &close) {
+        input.read_offset(next, close.chars().count());
+        return ReaderResult::Some((self.to_token)(
+          string,
+          TokenMeta::new_state_meta(current, next),
+        ));
+      }
+
+      match input.read(next) {
+        Some(ch) => string.push(ch),
+        None => {
+          return ReaderResult::Err((self.to_error)(
+            ReadError::UnterminatedString,
+            TokenMeta::new_state_meta(current, &opened),
+          ))
+        }
+      }
+    }
+  }
+
+  #[inline]
+  fn samples(&self) -> Vec<String> {
+    vec![format!("{}\"(a)\"", self.prefix)]
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use alloc::vec::Vec;
+
+  use super::*;
+
+  type Result = core::result::Result<(String, u64, u64), (ReadError, u64, u64)>;
+
+  fn lex(string: &str) -> Vec<Result> {
+    let reader = RawStringReader::new(
+      |string, meta: TokenMeta| Ok((string, meta.index_start(), meta.index_end())),
+      |error, meta: TokenMeta| (error, meta.index_start(), meta.index_end()),
+    );
+
+    super::super::lex(reader, string, |ch, current, next| {
+      Some((ch.into(), current.index() as u64, next.index() as u64))
+    })
+  }
+
+  #[test]
+  fn test_raw_string() {
+    assert_eq!(
+      lex("R\"(a\\n\")\" R\"xy()\"\n)x\")xy\""),
+      [
+        Ok(("a\\n\"".into(), 0, 9)),
+        Ok((")\"\n)x\"".into(), 10, 25))
+      ]
+    );
+  }
+
+  #[test]
+  fn test_not_raw_string() {
+    assert_eq!(
+      lex("R\"a b("),
+      [
+        Ok(("R".into(), 0, 1)),
+        Ok(("\"".into(), 1, 2)),
+        Ok(("a".into(), 2, 3)),
+        Ok(("b".into(), 4, 5)),
+        Ok(("(".into(), 5, 6)),
+      ]
+    );
+  }
+
+  #[test]
+  fn test_delimiter_len() {
+    assert_eq!(
+      lex("R\"éééééééééééééééé(a)éééééééééééééééé\""),
+      [Ok(("a".into(), 0, 38))]
+    );
+    assert_eq!(
+      lex("R\"aaaaaaaaaaaaaaaaa(a)aaaaaaaaaaaaaaaaa\"")[0],
+      Ok(("R".into(), 0, 1))
+    );
+  }
+
+  #[test]
+  fn test_unterminated() {
+    assert_eq!(
+      lex("R\"x(abc)\""),
+      [Err((ReadError::UnterminatedString, 0, 4))]
+    );
+  }
+}
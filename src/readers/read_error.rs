@@ -0,0 +1,34 @@
+use core::fmt;
+
+/// Errors the readers in `lexer::readers` report, mapped into the user's
+/// error type by the closure each reader is built with.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ReadError {
+  UnterminatedString,
+  InvalidEscape,
+  UnterminatedComment,
+  UnterminatedHeredoc,
+  MalformedNumber,
+  NumberOverflow,
+}
+
+impl ReadError {
+  #[inline]
+  pub fn as_str(&self) -> &'static str {
+    match *self {
+      ReadError::UnterminatedString => "unterminated string",
+      ReadError::InvalidEscape => "invalid escape",
+      ReadError::UnterminatedComment => "unterminated comment",
+      ReadError::UnterminatedHeredoc => "unterminated heredoc",
+      ReadError::MalformedNumber => "malformed number",
+      ReadError::NumberOverflow => "number overflow",
+    }
+  }
+}
+
+impl fmt::Display for ReadError {
+  #[inline]
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    f.write_str(self.as_str())
+  }
+}
@@ -0,0 +1,425 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use super::super::{Input, Reader, ReaderResult, Readers, State, TokenMeta};
+use super::ReadError;
+
+/// Reads quoted strings, processing `\n`, `\r`, `\t`, `\0`, `\\`, escaped
+/// quotes, `\xNN` (up to `\x7F`) and `\u{NNNN}` escapes. With `raw` set,
+/// `r"..."` and `r#"..."#` strings are read without escape processing. Every
+/// invalid escape is reported with its own span, after an unterminated
+/// string error spanning the opening quote if the string is not closed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct StringReader<F, G> {
+  quotes: &'static [char],
+  raw: bool,
+  multi_line: bool,
+  to_token: F,
+  to_error: G,
+}
+
+impl<F, G> StringReader<F, G> {
+  #[inline]
+  pub fn new(to_token: F, to_error: G) -> Self {
+    StringReader {
+      quotes: &['"'],
+      raw: false,
+      multi_line: true,
+      to_token,
+      to_error,
+    }
+  }
+
+  /// The chars that open a string, which must be closed by the same char.
+  #[inline]
+  pub fn quotes(mut self, quotes: &'static [char]) -> Self {
+    self.quotes = quotes;
+    self
+  }
+
+  /// Also read raw strings prefixed by `r` and any number of `#`s.
+  #[inline]
+  pub fn raw(mut self, raw: bool) -> Self {
+    self.raw = raw;
+    self
+  }
+
+  /// Allow line breaks inside strings, on by default.
+  #[inline]
+  pub fn multi_line(mut self, multi_line: bool) -> Self {
+    self.multi_line = multi_line;
+    self
+  }
+
+  #[inline]
+  fn is_quote(&self, ch: Option<char>) -> bool {
+    ch.is_some_and(|ch| self.quotes.contains(&ch))
+  }
+
+  /// Returns the length of the opening delimiter, its quote and the number
+  /// of `#`s for raw strings.
+  #[inline]
+  fn open(&self, input: &mut dyn Input, state: &State) -> Option<(usize, char, Option<usize>)> {
+    let ch = input.peek(state, 0);
+
+    if self.raw && ch == Some('r') {
+      let mut hashes = 0;
+
+      while input.peek(state, hashes + 1) == Some('#') {
+        hashes += 1;
+      }
+
+      let quote = input.peek(state, hashes + 1);
+
+      if self.is_quote(quote) {
+        return quote.map(|quote| (hashes + 2, quote, Some(hashes)));
+      }
+    }
+
+    if self.is_quote(ch) {
+      ch.map(|quote| (1, quote, None))
+    } else {
+      None
+    }
+  }
+
+  #[inline]
+  fn read_raw(
+    &self,
+    input: &mut dyn Input,
+    next: &mut State,
+    quote: char,
+    hashes: usize,
+  ) -> Result<String, ReadError> {
+    let mut string = String::new();
+
+    loop {
+      match input.peek(next, 0) {
+        Some(ch) if ch == quote && (1..=hashes).all(|i| input.peek(next, i) == Some('#')) => {
+          input.read_offset(next, hashes + 1);
+          return Ok(string);
+        }
+        Some(ch) if !self.multi_line && next.is_line_break(ch) => break,
+        Some(ch) => {
+          input.read(next);
+          string.push(ch);
+        }
+        None => break,
+      }
+    }
+
+    Err(ReadError::UnterminatedString)
+  }
+}
+
+impl<T, E, F, G> Reader<T, E> for StringReader<F, G>
+where
+  F: Fn(String, TokenMeta) -> T,
+  G: Fn(ReadError, TokenMeta) -> E,
+{
+  #[inline]
+  fn read(
+    &self,
+    _: &Readers<T, E>,
+    input: &mut dyn Input,
+    current: &State,
+    next: &mut State,
+  ) -> ReaderResult<T, E> {
+    let (len, quote, hashes) = match self.open(input, next) {
+      Some(open) => open,
+      None => return ReaderResult::None,
+    };
+
+    input.read_offset(next, len);
+    let opened = next.clone();
+
+    if let Some(hashes) = hashes {
+      return match self.read_raw(input, next, quote, hashes) {
+        Ok(string) => ReaderResult::Some((self.to_token)(
+          string,
+          TokenMeta::new_state_meta(current, next),
+        )),
+        Err(error) => ReaderResult::Err((self.to_error)(
+          error,
+          TokenMeta::new_state_meta(current, &opened),
+        )),
+      };
+    }
+
+    let mut string = String::new();
+    let mut errors = Vec::new();
+
+    loop {
+      let start = next.clone();
+
+      match input.peek(next, 0) {
+        Some(ch) if ch == quote => {
+          input.read(next);
+
+          return if errors.is_empty() {
+            ReaderResult::Some((self.to_token)(
+              string,
+              TokenMeta::new_state_meta(current, next),
+            ))
+          } else {
+            errors_result(errors)
+          };
+        }
+        Some(ch) if !self.multi_line && next.is_line_break(ch) => break,
+        Some('\\') => {
+          input.read(next);
+
+          match read_escape(input, next, quote) {
+            Ok(ch) => string.push(ch),
+            Err(ReadError::UnterminatedString) => break,
+            Err(error) => errors.push(Err((self.to_error)(
+              error,
+              TokenMeta::new_state_meta(&start, next),
+            ))),
+          }
+        }
+        Some(ch) => {
+          input.read(next);
+          string.push(ch);
+        }
+        None => break,
+      }
+    }
+
+    errors.insert(
+      0,
+      Err((self.to_error)(
+        ReadError::UnterminatedString,
+        TokenMeta::new_state_meta(current, &opened),
+      )),
+    );
+    errors_result(errors)
+  }
+
+  #[inline]
+  fn samples(&self) -> Vec<String> {
+    self
+      .quotes
+      .iter()
+      .map(|quote| format!("{}a{}", quote, quote))
+      .collect()
+  }
+}
+
+/// A single error on its own, several as `ReaderResult::Many`.
+#[inline]
+fn errors_result<T, E>(mut errors: Vec<Result<T, E>>) -> ReaderResult<T, E> {
+  if errors.len() == 1 {
+    match errors.pop() {
+      Some(Err(error)) => ReaderResult::Err(error),
+      _ => unreachable!(),
+    }
+  } else {
+    ReaderResult::Many(errors)
+  }
+}
+
+/// Reads the escape after a `\`, leaving `next` after the whole escape so
+/// errors can point at it.
+#[inline]
+fn read_escape(input: &mut dyn Input, next: &mut State, quote: char) -> Result<char, ReadError> {
+  match input.read(next) {
+    Some('n') => Ok('\n'),
+    Some('r') => Ok('\r'),
+    Some('t') => Ok('\t'),
+    Some('0') => Ok('\0'),
+    Some(ch @ '\\') | Some(ch @ '"') | Some(ch @ '\'') => Ok(ch),
+    Some(ch) if ch == quote => Ok(ch),
+    Some('x') => {
+      let mut value = 0;
+
+      for _ in 0..2 {
+        match input.peek(next, 0).and_then(|ch| ch.to_digit(16)) {
+          Some(digit) => {
+            input.read(next);
+            value = value * 16 + digit;
+          }
+          None => return Err(ReadError::InvalidEscape),
+        }
+      }
+
+      if value <= 0x7F {
+        char::from_u32(value).ok_or(ReadError::InvalidEscape)
+      } else {
+        Err(ReadError::InvalidEscape)
+      }
+    }
+    Some('u') => {
+      if input.peek(next, 0) != Some('{') {
+        return Err(ReadError::InvalidEscape);
+      }
+      input.read(next);
+
+      let mut digits = String::new();
+
+      loop {
+        match input.peek(next, 0) {
+          Some('}') => {
+            input.read(next);
+            break;
+          }
+          Some(ch) if ch != quote && !next.is_line_break(ch) => {
+            input.read(next);
+            digits.push(ch);
+          }
+          _ => return Err(ReadError::InvalidEscape),
+        }
+      }
+
+      if digits.is_empty() || digits.len() > 6 || !digits.chars().all(|ch| ch.is_ascii_hexdigit()) {
+        Err(ReadError::InvalidEscape)
+      } else {
+        u32::from_str_radix(&digits, 16)
+          .ok()
+          .and_then(char::from_u32)
+          .ok_or(ReadError::InvalidEscape)
+      }
+    }
+    Some(_) => Err(ReadError::InvalidEscape),
+    None => Err(ReadError::UnterminatedString),
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use alloc::vec::Vec;
+
+  type Result = core::result::Result<String, (ReadError, u64, u64)>;
+  type ToToken = fn(String, TokenMeta) -> Result;
+  type ToError = fn(ReadError, TokenMeta) -> (ReadError, u64, u64);
+
+  fn lex_with<F, G>(string: &str, reader: StringReader<F, G>) -> Vec<Result>
+  where
+    F: 'static + Fn(String, TokenMeta) -> Result,
+    G: 'static + Fn(ReadError, TokenMeta) -> (ReadError, u64, u64),
+  {
+    super::super::lex(reader, string, |_, _, _| None)
+  }
+
+  fn reader() -> StringReader<ToToken, ToError> {
+    StringReader::new(
+      |string, _| Ok(string),
+      |error, meta| (error, meta.index_start(), meta.index_end()),
+    )
+  }
+
+  fn lex(string: &str) -> Vec<Result> {
+    lex_with(string, reader())
+  }
+
+  #[test]
+  fn test_strings() {
+    assert_eq!(
+      lex(r#""abc" "a\"b\n\\" "é""#),
+      [Ok("abc".into()), Ok("a\"b\n\\".into()), Ok("é".into())]
+    );
+  }
+
+  #[test]
+  fn test_unicode_and_hex_escapes() {
+    assert_eq!(
+      lex(r#""\u{48}\u{e9}\u{1F600}\x41\x7f""#),
+      [Ok("Hé😀A\x7f".into())]
+    );
+  }
+
+  #[test]
+  fn test_errors() {
+    assert_eq!(
+      lex(r#""a\qb"#),
+      [
+        Err((ReadError::UnterminatedString, 0, 1)),
+        Err((ReadError::InvalidEscape, 2, 4))
+      ]
+    );
+    assert_eq!(
+      lex(r#" "abc"#),
+      [Err((ReadError::UnterminatedString, 1, 2))]
+    );
+    assert_eq!(lex(r#""ab\"#), [Err((ReadError::UnterminatedString, 0, 1))]);
+  }
+
+  #[test]
+  fn test_every_invalid_escape() {
+    assert_eq!(
+      lex(r#""\qa\w" "b""#),
+      [
+        Err((ReadError::InvalidEscape, 1, 3)),
+        Err((ReadError::InvalidEscape, 4, 6)),
+        Ok("b".into())
+      ]
+    );
+    assert_eq!(
+      lex(r#""\q\w"#),
+      [
+        Err((ReadError::UnterminatedString, 0, 1)),
+        Err((ReadError::InvalidEscape, 1, 3)),
+        Err((ReadError::InvalidEscape, 3, 5))
+      ]
+    );
+  }
+
+  #[test]
+  fn test_escape_error_spans() {
+    assert_eq!(lex(r#""a\x4""#), [Err((ReadError::InvalidEscape, 2, 5))]);
+    assert_eq!(lex(r#""a\x80""#), [Err((ReadError::InvalidEscape, 2, 6))]);
+    assert_eq!(
+      lex(r#""\u{110000}""#),
+      [Err((ReadError::InvalidEscape, 1, 11))]
+    );
+    assert_eq!(lex(r#""\u{zz}""#), [Err((ReadError::InvalidEscape, 1, 7))]);
+    assert_eq!(lex(r#""\u{+4}""#), [Err((ReadError::InvalidEscape, 1, 7))]);
+    assert_eq!(lex(r#""\u{}""#), [Err((ReadError::InvalidEscape, 1, 5))]);
+    assert_eq!(lex(r#""\u{12""#), [Err((ReadError::InvalidEscape, 1, 6))]);
+    assert_eq!(lex(r#""\u12""#), [Err((ReadError::InvalidEscape, 1, 3))]);
+  }
+
+  #[test]
+  fn test_quotes() {
+    let reader = reader().quotes(&['"', '\'']);
+
+    assert_eq!(
+      lex_with(r#"'a"b' "c'd" 'e\'f'"#, reader),
+      [Ok("a\"b".into()), Ok("c'd".into()), Ok("e'f".into())]
+    );
+  }
+
+  #[test]
+  fn test_raw() {
+    assert_eq!(
+      lex_with(r###"r"a\n" r#"b"c"# r##"d"#e"##"###, reader().raw(true)),
+      [Ok("a\\n".into()), Ok("b\"c".into()), Ok("d\"#e".into())]
+    );
+    assert_eq!(
+      lex_with("r#\"abc\"", reader().raw(true)),
+      [Err((ReadError::UnterminatedString, 0, 3))]
+    );
+    assert_eq!(lex(r#"r"a""#), [Ok("a".into())]);
+  }
+
+  #[test]
+  fn test_multi_line() {
+    assert_eq!(lex("\"a\nb\""), [Ok("a\nb".into())]);
+    assert_eq!(
+      lex_with("\"a\nb\"", reader().multi_line(false)),
+      [
+        Err((ReadError::UnterminatedString, 0, 1)),
+        Err((ReadError::UnterminatedString, 4, 5)),
+      ]
+    );
+    assert_eq!(
+      lex_with("r\"a\nb\"", reader().raw(true).multi_line(false)),
+      [
+        Err((ReadError::UnterminatedString, 0, 2)),
+        Err((ReadError::UnterminatedString, 5, 6)),
+      ]
+    );
+  }
+}
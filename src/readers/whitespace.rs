@@ -0,0 +1,100 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use super::super::{Input, Reader, ReaderResult, Readers, State};
+
+/// Skips runs of whitespace, `char::is_whitespace` unless another predicate
+/// is given.
+#[derive(Debug, Clone, Copy)]
+pub struct WhitespaceReader {
+  is_whitespace: fn(char) -> bool,
+}
+
+impl Default for WhitespaceReader {
+  #[inline]
+  fn default() -> Self {
+    WhitespaceReader {
+      is_whitespace: char::is_whitespace,
+    }
+  }
+}
+
+impl WhitespaceReader {
+  #[inline]
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  #[inline]
+  pub fn with_predicate(is_whitespace: fn(char) -> bool) -> Self {
+    WhitespaceReader { is_whitespace }
+  }
+}
+
+impl<T, E> Reader<T, E> for WhitespaceReader {
+  #[inline]
+  fn read(
+    &self,
+    _: &Readers<T, E>,
+    input: &mut dyn Input,
+    current: &State,
+    next: &mut State,
+  ) -> ReaderResult<T, E> {
+    while let Some(ch) = input.peek(next, 0) {
+      if (self.is_whitespace)(ch) {
+        input.read(next);
+      } else {
+        break;
+      }
+    }
+
+    if next.index() == current.index() {
+      ReaderResult::None
+    } else {
+      ReaderResult::Empty
+    }
+  }
+
+  #[inline]
+  fn samples(&self) -> Vec<String> {
+    [" ", "\t", "\n"]
+      .iter()
+      .filter(|sample| sample.chars().all(self.is_whitespace))
+      .map(|&sample| sample.into())
+      .collect()
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::super::super::ReadersBuilder;
+  use super::super::lex_with;
+  use super::*;
+
+  fn lex(reader: WhitespaceReader, string: &str) -> Vec<(char, usize)> {
+    lex_with(
+      ReadersBuilder::new().add(reader),
+      string,
+      |ch, current, _| Some((ch, current.index())),
+    )
+    .into_iter()
+    .map(|result: Result<_, ()>| result.unwrap())
+    .collect()
+  }
+
+  #[test]
+  fn test_whitespace() {
+    assert_eq!(
+      lex(WhitespaceReader::new(), " a \t\n b\u{a0}"),
+      [('a', 1), ('b', 6)]
+    );
+  }
+
+  #[test]
+  fn test_predicate() {
+    let reader = WhitespaceReader::with_predicate(|ch| ch == ' ');
+
+    assert_eq!(lex(reader, " a\n b"), [('a', 1), ('\n', 2), ('b', 4)]);
+    assert_eq!(Reader::<(), ()>::samples(&reader), [" "]);
+  }
+}
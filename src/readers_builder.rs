@@ -1,37 +1,463 @@
-use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::sync::Arc;
 use alloc::vec::Vec;
+use core::cmp::Reverse;
+use core::marker::PhantomData;
 
-use super::{Reader, Readers};
+use peek_nth::IteratorExt;
 
-pub struct ReadersBuilder<T, E>(pub(crate) Vec<Box<dyn Reader<T, E>>>);
+use super::{Input, Named, Reader, ReaderResult, Readers, ShadowWarning, State};
 
-unsafe impl<T, E> Sync for ReadersBuilder<T, E>
-where
-  T: Sync,
-  E: Sync,
-{
+struct Entry<D: ?Sized> {
+  reader: Arc<D>,
+  priority: i32,
+  samples: Vec<String>,
 }
-unsafe impl<T, E> Send for ReadersBuilder<T, E>
+
+/// The readers of a `ReadersBuilder` or a `SyncReadersBuilder`, boxed as `D`.
+pub(crate) struct Entries<T, E, D: ?Sized> {
+  entries: Vec<Entry<D>>,
+  last: Option<usize>,
+  check_shadows: bool,
+  marker: PhantomData<fn(T, E)>,
+}
+
+impl<T, E, D: ?Sized> Default for Entries<T, E, D> {
+  #[inline]
+  fn default() -> Self {
+    Entries {
+      entries: Vec::new(),
+      last: None,
+      check_shadows: false,
+      marker: PhantomData,
+    }
+  }
+}
+
+impl<T, E, D> Entries<T, E, D>
 where
-  T: Send,
-  E: Send,
+  D: ?Sized + Reader<T, E>,
 {
+  #[inline]
+  pub(crate) fn add(mut self, reader: Arc<D>) -> Self {
+    self.last = Some(self.entries.len());
+    self.entries.push(Entry {
+      reader,
+      priority: 0,
+      samples: Vec::new(),
+    });
+    self
+  }
+
+  #[inline]
+  pub(crate) fn priority(mut self, priority: i32) -> Self {
+    if let Some(index) = self.last {
+      self.entries[index].priority = priority;
+    }
+    self
+  }
+
+  #[inline]
+  pub(crate) fn samples<I, S>(mut self, samples: I) -> Self
+  where
+    I: IntoIterator<Item = S>,
+    S: Into<String>,
+  {
+    if let Some(index) = self.last {
+      self.entries[index]
+        .samples
+        .extend(samples.into_iter().map(Into::into));
+    }
+    self
+  }
+
+  #[inline]
+  pub(crate) fn check_shadows(mut self) -> Self {
+    self.check_shadows = true;
+    self
+  }
+
+  #[inline]
+  pub(crate) fn insert(mut self, index: usize, reader: Arc<D>, priority: i32) -> Self {
+    self.entries.insert(
+      index,
+      Entry {
+        reader,
+        priority,
+        samples: Vec::new(),
+      },
+    );
+    self.last = Some(index);
+    self
+  }
+
+  #[inline]
+  pub(crate) fn insert_before(self, name: &str, reader: Arc<D>) -> Self {
+    let index = self.expect_position(name);
+    let priority = self.entries[index].priority;
+
+    self.insert(index, reader, priority)
+  }
+
+  #[inline]
+  pub(crate) fn insert_after(self, name: &str, reader: Arc<D>) -> Self {
+    let index = self.expect_position(name) + 1;
+    let priority = self.entries[index - 1].priority;
+
+    self.insert(index, reader, priority)
+  }
+
+  #[inline]
+  pub(crate) fn replace(mut self, index: usize, reader: Arc<D>) -> Self {
+    let priority = self.entries[index].priority;
+
+    self.entries[index] = Entry {
+      reader,
+      priority,
+      samples: Vec::new(),
+    };
+    self.last = Some(index);
+    self
+  }
+
+  #[inline]
+  pub(crate) fn remove(mut self, name: &str) -> Self {
+    let index = self.expect_position(name);
+
+    self.entries.remove(index);
+    self.last = None;
+    self
+  }
+
+  #[inline]
+  pub(crate) fn position(&self, name: &str) -> Option<usize> {
+    self
+      .entries
+      .iter()
+      .position(|entry| entry.reader.name() == name)
+  }
+
+  #[inline]
+  pub(crate) fn names(&self) -> impl Iterator<Item = &str> {
+    self.entries.iter().map(|entry| entry.reader.name())
+  }
+
+  #[inline]
+  pub(crate) fn expect_position(&self, name: &str) -> usize {
+    match self.position(name) {
+      Some(index) => index,
+      None => panic!("no reader named {:?}", name),
+    }
+  }
+
+  /// Orders the readers by priority, returning them with their samples when
+  /// they are to be checked for shadowing.
+  #[inline]
+  pub(crate) fn build(mut self) -> (Vec<Arc<D>>, Option<Vec<Vec<String>>>) {
+    // a stable sort, equal priorities stay in the order they were added
+    self.entries.sort_by_key(|entry| Reverse(entry.priority));
+
+    let check_shadows = self.check_shadows;
+    let mut samples = Vec::with_capacity(self.entries.len());
+    let mut readers = Vec::with_capacity(self.entries.len());
+
+    for entry in self.entries {
+      let mut entry_samples = entry.reader.samples();
+      entry_samples.extend(entry.samples);
+      samples.push(entry_samples);
+      readers.push(entry.reader);
+    }
+
+    if check_shadows {
+      (readers, Some(samples))
+    } else {
+      (readers, None)
+    }
+  }
+}
+
+/// Collects readers for `Readers`. Readers with higher priorities are tried
+/// first, readers of equal priority, by default 0, in the order they were
+/// added.
+pub struct ReadersBuilder<T, E>(Entries<T, E, dyn Reader<T, E>>);
+
+impl<T, E> Default for ReadersBuilder<T, E> {
+  #[inline]
+  fn default() -> Self {
+    ReadersBuilder(Entries::default())
+  }
 }
 
 impl<T, E> ReadersBuilder<T, E> {
   #[inline]
   pub fn new() -> Self {
-    ReadersBuilder(Vec::new())
+    Self::default()
   }
 
   #[inline]
-  pub fn add<R: 'static + Reader<T, E>>(mut self, reader: R) -> Self {
-    self.0.push(Box::new(reader));
-    self
+  #[allow(clippy::should_implement_trait)]
+  pub fn add<R: 'static + Reader<T, E>>(self, reader: R) -> Self {
+    ReadersBuilder(self.0.add(Arc::new(reader)))
+  }
+
+  #[inline]
+  pub fn add_fn<F>(self, f: F) -> Self
+  where
+    F: 'static + Fn(&Readers<T, E>, &mut dyn Input, &State, &mut State) -> ReaderResult<T, E>,
+  {
+    self.add(f)
+  }
+
+  /// Adds `reader` under `name`, so later additions can be placed around it.
+  #[inline]
+  pub fn add_named<S, R>(self, name: S, reader: R) -> Self
+  where
+    S: Into<String>,
+    R: 'static + Reader<T, E>,
+  {
+    self.add(Named::new(name, reader))
+  }
+
+  /// Sets the priority of the reader added, inserted or replaced last.
+  #[inline]
+  pub fn priority(self, priority: i32) -> Self {
+    ReadersBuilder(self.0.priority(priority))
+  }
+
+  /// Adds inputs the reader added, inserted or replaced last reads, besides
+  /// its `Reader::samples`, to check for readers shadowing it.
+  #[inline]
+  pub fn samples<I, S>(self, samples: I) -> Self
+  where
+    I: IntoIterator<Item = S>,
+    S: Into<String>,
+  {
+    ReadersBuilder(self.0.samples(samples))
+  }
+
+  /// Probes the readers with their samples on `build`, see
+  /// `ReadersBuilder::build`.
+  #[inline]
+  pub fn check_shadows(self) -> Self {
+    ReadersBuilder(self.0.check_shadows())
+  }
+
+  /// Inserts `reader` right before the reader named `name`, with its
+  /// priority.
+  ///
+  /// # Panics
+  ///
+  /// If no reader is named `name`.
+  #[inline]
+  pub fn insert_before<R>(self, name: &str, reader: R) -> Self
+  where
+    R: 'static + Reader<T, E>,
+  {
+    ReadersBuilder(self.0.insert_before(name, Arc::new(reader)))
+  }
+
+  /// Inserts `reader` right after the reader named `name`, with its priority.
+  ///
+  /// # Panics
+  ///
+  /// If no reader is named `name`.
+  #[inline]
+  pub fn insert_after<R>(self, name: &str, reader: R) -> Self
+  where
+    R: 'static + Reader<T, E>,
+  {
+    ReadersBuilder(self.0.insert_after(name, Arc::new(reader)))
+  }
+
+  /// Replaces the reader named `name` with `reader`, which keeps the name and
+  /// priority.
+  ///
+  /// # Panics
+  ///
+  /// If no reader is named `name`.
+  #[inline]
+  pub fn replace<R>(self, name: &str, reader: R) -> Self
+  where
+    R: 'static + Reader<T, E>,
+  {
+    let index = self.0.expect_position(name);
+    ReadersBuilder(self.0.replace(index, Arc::new(Named::new(name, reader))))
+  }
+
+  /// Removes the reader named `name`.
+  ///
+  /// # Panics
+  ///
+  /// If no reader is named `name`.
+  #[inline]
+  pub fn remove(self, name: &str) -> Self {
+    ReadersBuilder(self.0.remove(name))
+  }
+
+  /// The index of the first reader named `name`, in the order readers were
+  /// added.
+  #[inline]
+  pub fn position(&self, name: &str) -> Option<usize> {
+    self.0.position(name)
+  }
+
+  /// The names of the readers, in the order they were added.
+  #[inline]
+  pub fn names(&self) -> impl Iterator<Item = &str> {
+    self.0.names()
   }
 
+  /// Orders the readers by priority. With `check_shadows`, each reader is
+  /// then run on the samples of the readers after it, recording a
+  /// `ShadowWarning` in `Readers::warnings` for every sample an earlier
+  /// reader takes. Probing calls `Reader::read` at build time, so readers
+  /// with side effects, like counting or interning symbols, see the samples
+  /// too.
   #[inline]
   pub fn build(self) -> Readers<T, E> {
-    Readers::from(self)
+    let (readers, samples) = self.0.build();
+    let mut readers = Readers::from_arcs(readers);
+
+    if let Some(samples) = samples {
+      readers.warnings = shadow_warnings(&readers, &samples);
+    }
+    readers
+  }
+
+  /// Builds the readers with `check_shadows`, or returns the warnings if any
+  /// reader is shadowed.
+  #[inline]
+  pub fn build_checked(self) -> Result<Readers<T, E>, Vec<ShadowWarning>> {
+    let readers = self.check_shadows().build();
+
+    if readers.warnings().is_empty() {
+      Ok(readers)
+    } else {
+      Err(readers.warnings().to_vec())
+    }
+  }
+}
+
+#[inline]
+pub(crate) fn shadow_warnings<T, E>(
+  readers: &Readers<T, E>,
+  samples: &[Vec<String>],
+) -> Vec<ShadowWarning> {
+  let mut warnings = Vec::new();
+
+  for (index, samples) in samples.iter().enumerate() {
+    for sample in samples {
+      // samples their own reader does not read tell nothing
+      if !reads(readers, index, sample) {
+        continue;
+      }
+      if let Some(shadow) = (0..index).find(|&shadow| reads(readers, shadow, sample)) {
+        warnings.push(ShadowWarning::new(
+          readers[shadow].name(),
+          readers[index].name(),
+          sample,
+        ));
+      }
+    }
+  }
+
+  warnings
+}
+
+#[inline]
+fn reads<T, E>(readers: &Readers<T, E>, index: usize, sample: &str) -> bool {
+  let mut input = sample.chars().peekable_nth();
+  let current = State::new();
+  let mut next = current.clone();
+
+  !matches!(
+    readers[index].read(readers, &mut input, &current, &mut next),
+    ReaderResult::None
+  )
+}
+
+#[cfg(test)]
+mod test {
+  use alloc::string::ToString;
+  use alloc::vec::Vec;
+
+  use super::super::{char_class, literal};
+  use super::*;
+
+  fn named() -> ReadersBuilder<&'static str, ()> {
+    ReadersBuilder::new()
+      .add_named("a", literal("a", |_| "a"))
+      .add_named("b", literal("b", |_| "b"))
+  }
+
+  fn lex(builder: ReadersBuilder<&'static str, ()>, string: &str) -> Vec<&'static str> {
+    builder
+      .build()
+      .read(string.chars())
+      .map(Result::unwrap)
+      .collect()
+  }
+
+  #[test]
+  fn test_names() {
+    let builder = named()
+      .insert_before("b", Named::new("ab", literal("ab", |_| "ab")))
+      .insert_after("b", Named::new("c", literal("c", |_| "c")));
+
+    assert_eq!(builder.names().collect::<Vec<_>>(), ["a", "ab", "b", "c"]);
+    assert_eq!(builder.position("c"), Some(3));
+    assert_eq!(builder.position("d"), None);
+    assert_eq!(lex(builder, "abc"), ["a", "b", "c"]);
+
+    let builder = named()
+      .insert_before("a", Named::new("ab", literal("ab", |_| "ab")))
+      .replace("b", literal("b", |_| "B"))
+      .remove("a");
+
+    assert_eq!(builder.names().collect::<Vec<_>>(), ["ab", "b"]);
+    assert_eq!(lex(builder, "abb"), ["ab", "B"]);
+  }
+
+  #[test]
+  #[should_panic(expected = "no reader named \"d\"")]
+  fn test_missing_name() {
+    let _ = named().remove("d");
+  }
+
+  #[test]
+  fn test_priority() {
+    let builder = ReadersBuilder::<&'static str, ()>::new()
+      .add_named("any", char_class(|_| true, |_, _| "any"))
+      .priority(-1)
+      .add_named("a", literal("a", |_| "a"))
+      .add_named("b", literal("b", |_| "b"))
+      .priority(1)
+      .insert_after("any", Named::new("c", literal("c", |_| "c")));
+
+    assert_eq!(
+      builder.build().names().collect::<Vec<_>>(),
+      ["b", "a", "any", "c"]
+    );
+  }
+
+  #[test]
+  fn test_shadow_warnings() {
+    let shadowed = || {
+      ReadersBuilder::<&'static str, ()>::new()
+        .add_named("any", char_class(|_| true, |_, _| "any"))
+        .add_named("a", literal("a", |_| "a"))
+        .samples(["a", "b"])
+    };
+
+    assert!(shadowed().build().warnings().is_empty());
+    assert_eq!(
+      shadowed().check_shadows().build().warnings(),
+      [ShadowWarning::new("any", "a", "a")]
+    );
+    assert_eq!(
+      shadowed().build_checked().err().unwrap()[0].to_string(),
+      "any shadows a, which never reads \"a\""
+    );
+    assert!(shadowed().priority(1).build_checked().is_ok());
   }
 }
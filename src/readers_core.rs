@@ -0,0 +1,185 @@
+use alloc::sync::Arc;
+use alloc::{boxed::Box, vec::Vec};
+use core::ops::Deref;
+
+use peek_nth::{IteratorExt, PeekableNth};
+
+use super::SymbolTable;
+use super::{
+  Attempt, IndentError, IndentIter, Indentation, Reader, ReadersBuilder, ShadowWarning, State,
+  TokenIter, TokenMeta,
+};
+
+/// The readers to try, in order, at every position. Readers need not be
+/// thread safe, so they may hold an `Rc` or a `Cell`, see `SyncReaders` for
+/// readers shared by threads.
+///
+/// ```
+/// use lexer::{ReaderResult, ReadersBuilder};
+/// use std::rc::Rc;
+///
+/// let rc = Rc::new(());
+///
+/// ReadersBuilder::<(), ()>::new()
+///   .add_fn(move |_, _, _, _| {
+///     let _ = &rc;
+///     ReaderResult::None
+///   })
+///   .build();
+/// ```
+pub struct Readers<T, E> {
+  pub(crate) readers: Vec<Arc<dyn Reader<T, E>>>,
+  pub(crate) symbols: Arc<SymbolTable>,
+  pub(crate) observer: Option<Arc<Observer>>,
+  pub(crate) warnings: Vec<ShadowWarning>,
+}
+
+/// Called with every reader attempt, see `Readers::with_observer`.
+pub type Observer = dyn Fn(&Attempt) + Send + Sync;
+
+impl<T, E> From<ReadersBuilder<T, E>> for Readers<T, E> {
+  #[inline]
+  fn from(readers_builder: ReadersBuilder<T, E>) -> Readers<T, E> {
+    readers_builder.build()
+  }
+}
+
+impl<T, E> From<Vec<Box<dyn Reader<T, E>>>> for Readers<T, E> {
+  #[inline]
+  fn from(vec: Vec<Box<dyn Reader<T, E>>>) -> Readers<T, E> {
+    Readers::from_arcs(vec.into_iter().map(Arc::from).collect())
+  }
+}
+
+impl<T, E> Default for Readers<T, E> {
+  #[inline]
+  fn default() -> Self {
+    Readers::from(Vec::new())
+  }
+}
+
+impl<T, E> Readers<T, E> {
+  #[inline]
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  #[inline]
+  pub(crate) fn from_arcs(readers: Vec<Arc<dyn Reader<T, E>>>) -> Self {
+    Readers {
+      readers,
+      symbols: Arc::default(),
+      observer: None,
+      warnings: Vec::new(),
+    }
+  }
+
+  /// The symbols readers intern token text in, shared by every reader.
+  #[inline(always)]
+  pub fn symbols(&self) -> &SymbolTable {
+    &self.symbols
+  }
+
+  /// Interns into `symbols` instead, to share one table between lexers.
+  #[inline]
+  pub fn with_symbols(mut self, symbols: Arc<SymbolTable>) -> Self {
+    self.symbols = symbols;
+    self
+  }
+
+  /// Calls `observer` with every reader attempt while reading, which
+  /// readers were tried at each position, in order, how far each got and
+  /// what it returned.
+  #[inline]
+  pub fn with_observer<F>(mut self, observer: F) -> Self
+  where
+    F: 'static + Fn(&Attempt) + Send + Sync,
+  {
+    self.observer = Some(Arc::new(observer));
+    self
+  }
+
+  #[inline(always)]
+  pub(crate) fn observer(&self) -> Option<&Observer> {
+    self.observer.as_deref()
+  }
+
+  /// Readers shadowed by readers tried before them, empty unless built with
+  /// `ReadersBuilder::check_shadows`.
+  #[inline(always)]
+  pub fn warnings(&self) -> &[ShadowWarning] {
+    &self.warnings
+  }
+
+  /// The index of the first reader named `name`.
+  #[inline]
+  pub fn position(&self, name: &str) -> Option<usize> {
+    self.readers.iter().position(|reader| reader.name() == name)
+  }
+
+  /// The names of the readers, in the order they are tried.
+  #[inline]
+  pub fn names(&self) -> impl Iterator<Item = &str> {
+    self.readers.iter().map(|reader| reader.name())
+  }
+
+  #[inline]
+  pub fn get(&self, index: usize) -> Option<&dyn Reader<T, E>> {
+    self.readers.get(index).map(Arc::as_ref)
+  }
+
+  /// The reader at `index`, unless it is shared with the `SyncReaders` these
+  /// readers came from.
+  #[inline]
+  pub fn get_mut(&mut self, index: usize) -> Option<&mut (dyn Reader<T, E> + 'static)> {
+    self.readers.get_mut(index).and_then(Arc::get_mut)
+  }
+
+  #[inline]
+  pub fn read<'a, I>(&'a self, iter: I) -> PeekableNth<TokenIter<'a, T, E, I>>
+  where
+    I: Iterator<Item = char>,
+  {
+    TokenIter::new(self, iter).peekable_nth()
+  }
+
+  #[inline]
+  pub fn read_with_state<'a, I>(
+    &'a self,
+    iter: I,
+    state: State,
+  ) -> PeekableNth<TokenIter<'a, T, E, I>>
+  where
+    I: Iterator<Item = char>,
+  {
+    TokenIter::new_with_state(self, iter, state).peekable_nth()
+  }
+
+  /// Like `read`, inserting `Indentation` tokens for the offside rule, see
+  /// `IndentIter`.
+  #[inline]
+  pub fn read_indented<'a, I, F, G>(
+    &'a self,
+    iter: I,
+    to_token: F,
+    to_error: G,
+  ) -> PeekableNth<IndentIter<'a, T, E, I, F, G>>
+  where
+    I: Iterator<Item = char>,
+    F: Fn(Indentation, TokenMeta) -> T,
+    G: Fn(IndentError, TokenMeta) -> E,
+  {
+    IndentIter::new(TokenIter::new(self, iter), to_token, to_error).peekable_nth()
+  }
+}
+
+/// Readers are only read through `Deref`, adding them goes through
+/// `ReadersBuilder`, which keeps them in priority order.
+impl<T, E> Deref for Readers<T, E> {
+  type Target = [Arc<dyn Reader<T, E>>];
+
+  #[inline]
+  fn deref(&self) -> &Self::Target {
+    &self.readers
+  }
+}
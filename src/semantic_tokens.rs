@@ -0,0 +1,250 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use super::Token;
+
+/// LSP `semanticTokens` data, five `u32`s per token: delta line, delta start
+/// char, length, token type and token modifiers bitset. Positions are worked
+/// out from the source and the index span of each token, in the UTF-16 code
+/// units and the `\n`, `\r\n` and `\r` line breaks LSP uses, whatever column
+/// and newline modes the tokens were read with. Tokens spanning several lines
+/// are split into one token per line.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct SemanticTokens {
+  data: Vec<u32>,
+}
+
+/// One `SemanticTokensEdit` of a `semanticTokens/full/delta` response.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[serde(rename_all = "camelCase")]
+pub struct SemanticTokensEdit {
+  start: u32,
+  delete_count: u32,
+  data: Vec<u32>,
+}
+
+impl SemanticTokens {
+  #[inline]
+  pub fn encode<'a, V, I, F>(source: &str, tokens: I, mut f: F) -> Self
+  where
+    V: 'a,
+    I: IntoIterator<Item = &'a Token<V>>,
+    F: FnMut(&V) -> Option<(u32, u32)>,
+  {
+    let chars: Vec<char> = source.chars().collect();
+    let positions = positions(&chars);
+    let mut lines = Vec::new();
+
+    for token in tokens {
+      let (token_type, token_modifiers) = match f(token.value()) {
+        Some(kind) => kind,
+        None => continue,
+      };
+      let meta = token.meta();
+      let start = chars.len().min(meta.index_start() as usize);
+      let end = chars.len().min(meta.index_end() as usize);
+      let mut line_start = start;
+
+      for index in start..=end {
+        if index == end || chars[index] == '\n' || chars[index] == '\r' {
+          let (line, col) = positions[line_start];
+          let length = positions[index].1 - col;
+
+          if length > 0 {
+            lines.push((line, col, length, token_type, token_modifiers));
+          }
+          line_start = index + 1;
+        }
+      }
+    }
+
+    lines.sort_by_key(|&(line, start, ..)| (line, start));
+
+    let mut data = Vec::with_capacity(lines.len() * 5);
+    let mut prev_line = 0;
+    let mut prev_start = 0;
+
+    for (line, start, length, token_type, token_modifiers) in lines {
+      let delta_line = line - prev_line;
+      let delta_start = if delta_line == 0 {
+        start - prev_start
+      } else {
+        start
+      };
+
+      data.extend_from_slice(&[delta_line, delta_start, length, token_type, token_modifiers]);
+
+      prev_line = line;
+      prev_start = start;
+    }
+
+    SemanticTokens { data }
+  }
+
+  #[inline(always)]
+  pub fn data(&self) -> &[u32] {
+    &self.data
+  }
+  #[inline(always)]
+  pub fn into_data(self) -> Vec<u32> {
+    self.data
+  }
+
+  #[inline(always)]
+  pub fn len(&self) -> usize {
+    self.data.len() / 5
+  }
+  #[inline(always)]
+  pub fn is_empty(&self) -> bool {
+    self.data.is_empty()
+  }
+
+  /// The edits that turn `previous` into `self`, empty when both are equal.
+  #[inline]
+  pub fn edits(&self, previous: &SemanticTokens) -> Vec<SemanticTokensEdit> {
+    let old = previous.data();
+    let new = self.data();
+
+    let prefix = old
+      .iter()
+      .zip(new.iter())
+      .take_while(|(a, b)| a == b)
+      .count();
+    let suffix = old[prefix..]
+      .iter()
+      .rev()
+      .zip(new[prefix..].iter().rev())
+      .take_while(|(a, b)| a == b)
+      .count();
+
+    if prefix == old.len() && prefix == new.len() {
+      Vec::new()
+    } else {
+      vec![SemanticTokensEdit {
+        start: prefix as u32,
+        delete_count: (old.len() - prefix - suffix) as u32,
+        data: new[prefix..new.len() - suffix].into(),
+      }]
+    }
+  }
+}
+
+/// The LSP line and UTF-16 column of every char, and of the end of the
+/// source.
+#[inline]
+fn positions(chars: &[char]) -> Vec<(u32, u32)> {
+  let mut positions = Vec::with_capacity(chars.len() + 1);
+  let mut line = 0;
+  let mut col = 0;
+
+  for (index, &ch) in chars.iter().enumerate() {
+    positions.push((line, col));
+
+    if ch == '\n' || (ch == '\r' && chars.get(index + 1) != Some(&'\n')) {
+      line += 1;
+      col = 0;
+    } else {
+      col += ch.len_utf16() as u32;
+    }
+  }
+  positions.push((line, col));
+
+  positions
+}
+
+impl From<Vec<u32>> for SemanticTokens {
+  #[inline]
+  fn from(data: Vec<u32>) -> Self {
+    SemanticTokens { data }
+  }
+}
+
+impl SemanticTokensEdit {
+  #[inline(always)]
+  pub fn start(&self) -> u32 {
+    self.start
+  }
+  #[inline(always)]
+  pub fn delete_count(&self) -> u32 {
+    self.delete_count
+  }
+  #[inline(always)]
+  pub fn data(&self) -> &[u32] {
+    &self.data
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::super::TokenMeta;
+  use super::*;
+
+  fn token(index_start: u64, index_end: u64, value: u32) -> Token<u32> {
+    Token::new(TokenMeta::new(index_start, index_end, 1, 1, 1, 1), value)
+  }
+
+  #[test]
+  fn test_encode() {
+    let source = "abc d\n\n    efg h";
+    let tokens = [
+      token(11, 14, 1),
+      token(0, 3, 0),
+      token(4, 5, 2),
+      token(15, 16, 0),
+    ];
+    let semantic_tokens = SemanticTokens::encode(source, tokens.iter(), |&value| {
+      if value == 2 {
+        None
+      } else {
+        Some((value, 1 << value))
+      }
+    });
+
+    assert_eq!(semantic_tokens.len(), 3);
+    assert_eq!(
+      semantic_tokens.data(),
+      &[0, 0, 3, 0, 1, 2, 4, 3, 1, 2, 0, 4, 1, 0, 1]
+    );
+  }
+
+  #[test]
+  fn test_encode_splits_multiline() {
+    let source = "a \"b\r\ncd\n\ne\" f";
+    let tokens = [token(2, 12, 5), token(13, 14, 1)];
+
+    assert_eq!(
+      SemanticTokens::encode(source, tokens.iter(), |&v| Some((v, 0))).data(),
+      &[0, 2, 2, 5, 0, 1, 0, 2, 5, 0, 2, 0, 2, 5, 0, 0, 3, 1, 1, 0]
+    );
+  }
+
+  #[test]
+  fn test_encode_utf16() {
+    let source = "é😀 x";
+    let tokens = [token(1, 2, 0), token(3, 4, 1)];
+
+    assert_eq!(
+      SemanticTokens::encode(source, tokens.iter(), |&v| Some((v, 0))).data(),
+      &[0, 1, 2, 0, 0, 0, 3, 1, 1, 0]
+    );
+  }
+
+  #[test]
+  fn test_edits() {
+    let previous = SemanticTokens::from(vec![0, 0, 3, 0, 0, 1, 2, 4, 1, 0, 0, 5, 1, 2, 0]);
+    let current = SemanticTokens::from(vec![0, 0, 3, 0, 0, 2, 2, 4, 1, 0, 0, 5, 1, 2, 0]);
+    let edits = current.edits(&previous);
+
+    assert_eq!(edits.len(), 1);
+    assert_eq!(edits[0].start(), 5);
+    assert_eq!(edits[0].delete_count(), 1);
+    assert_eq!(edits[0].data(), &[2]);
+
+    assert!(current.edits(&current).is_empty());
+
+    let edits = SemanticTokens::default().edits(&previous);
+    assert_eq!(edits[0].start(), 0);
+    assert_eq!(edits[0].delete_count(), 15);
+    assert!(edits[0].data().is_empty());
+  }
+}
@@ -0,0 +1,50 @@
+use alloc::string::String;
+use core::fmt;
+
+/// A reader that reads a sample of a reader tried after it, so the later one
+/// never gets to read that input. Found by `ReadersBuilder::build`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ShadowWarning {
+  reader: String,
+  shadowed: String,
+  sample: String,
+}
+
+impl ShadowWarning {
+  #[inline]
+  pub fn new<S>(reader: S, shadowed: S, sample: S) -> Self
+  where
+    S: Into<String>,
+  {
+    ShadowWarning {
+      reader: reader.into(),
+      shadowed: shadowed.into(),
+      sample: sample.into(),
+    }
+  }
+
+  /// The name of the reader that is tried first.
+  #[inline(always)]
+  pub fn reader(&self) -> &str {
+    &self.reader
+  }
+  #[inline(always)]
+  pub fn shadowed(&self) -> &str {
+    &self.shadowed
+  }
+  #[inline(always)]
+  pub fn sample(&self) -> &str {
+    &self.sample
+  }
+}
+
+impl fmt::Display for ShadowWarning {
+  #[inline]
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(
+      f,
+      "{} shadows {}, which never reads {:?}",
+      self.reader, self.shadowed, self.sample
+    )
+  }
+}
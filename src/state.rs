@@ -1,13 +1,193 @@
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+use core::cmp::Ordering;
+use core::hash::{Hash, Hasher};
+
+#[cfg(feature = "grapheme")]
+use unicode_segmentation::UnicodeSegmentation;
+
+/// How `State` advances its column for every char it reads.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ColumnMode {
+  /// one column per `char`
+  Char,
+  /// one column per UTF-8 byte
+  Utf8,
+  /// one column per UTF-16 code unit, what LSP clients expect
+  Utf16,
+  /// one column per `char`, tabs jump to the next multiple of the tab width
+  Visual(u32),
+  /// one column per extended grapheme cluster
+  #[cfg(feature = "grapheme")]
+  Grapheme,
+}
+
+impl Default for ColumnMode {
+  #[inline]
+  fn default() -> Self {
+    ColumnMode::Char
+  }
+}
+
+impl ColumnMode {
+  #[inline]
+  #[cfg_attr(not(feature = "grapheme"), allow(unused_variables))]
+  fn next_col(&self, col: u64, ch: char, same_grapheme: bool) -> u64 {
+    match *self {
+      ColumnMode::Char => col + 1,
+      ColumnMode::Utf8 => col + ch.len_utf8() as u64,
+      ColumnMode::Utf16 => col + ch.len_utf16() as u64,
+      ColumnMode::Visual(tab_width) => {
+        if ch == '\t' {
+          let tab_width = tab_width.max(1) as u64;
+          ((col - 1) / tab_width + 1) * tab_width + 1
+        } else {
+          col + 1
+        }
+      }
+      #[cfg(feature = "grapheme")]
+      ColumnMode::Grapheme => {
+        if same_grapheme {
+          col
+        } else {
+          col + 1
+        }
+      }
+    }
+  }
+}
+
+/// What the current grapheme cluster holds, for the flag and emoji rules that
+/// look further back than the previous char.
+#[cfg(feature = "grapheme")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+enum Cluster {
+  Other,
+  /// an odd number of regional indicators, half a flag
+  Regional,
+  /// a pictographic followed by extending chars
+  Pictographic,
+  /// a pictographic, extending chars and a zero width joiner
+  PictographicZwj,
+}
+
+#[cfg(feature = "grapheme")]
+impl Cluster {
+  /// Returns whether `ch` continues the cluster `prev` is in, and moves on to
+  /// the cluster `ch` is in.
+  #[inline]
+  fn read(&mut self, prev: Option<char>, ch: char) -> bool {
+    let same_grapheme = match prev {
+      Some(prev) if is_regional_indicator(prev) && is_regional_indicator(ch) => {
+        *self == Cluster::Regional
+      }
+      Some('\u{200D}') if *self == Cluster::PictographicZwj && is_pictographic(ch) => true,
+      Some(prev) => is_one_grapheme(&[prev, ch]),
+      None => false,
+    };
+
+    *self = match *self {
+      _ if !same_grapheme => Cluster::start(ch),
+      Cluster::Pictographic if ch == '\u{200D}' => Cluster::PictographicZwj,
+      Cluster::Pictographic => Cluster::Pictographic,
+      Cluster::PictographicZwj if is_pictographic(ch) => Cluster::Pictographic,
+      _ => Cluster::Other,
+    };
+
+    same_grapheme
+  }
+
+  #[inline]
+  fn start(ch: char) -> Self {
+    if is_regional_indicator(ch) {
+      Cluster::Regional
+    } else if is_pictographic(ch) {
+      Cluster::Pictographic
+    } else {
+      Cluster::Other
+    }
+  }
+}
+
+#[cfg(feature = "grapheme")]
+#[inline]
+fn is_regional_indicator(ch: char) -> bool {
+  ('\u{1F1E6}'..='\u{1F1FF}').contains(&ch)
+}
+
+/// Extended pictographics are the only chars a zero width joiner after a
+/// pictographic joins to, other than the extending ones that join to anything.
+#[cfg(feature = "grapheme")]
+#[inline]
+fn is_pictographic(ch: char) -> bool {
+  !ch.is_ascii() && !is_one_grapheme(&['a', ch]) && is_one_grapheme(&['\u{1F600}', '\u{200D}', ch])
+}
+
+#[cfg(feature = "grapheme")]
+#[inline]
+fn is_one_grapheme(chars: &[char]) -> bool {
+  let mut buf = [0u8; 16];
+  let mut len = 0;
+
+  for ch in chars {
+    len += ch.encode_utf8(&mut buf[len..]).len();
+  }
+
+  match core::str::from_utf8(&buf[..len]) {
+    Ok(string) => string.graphemes(true).count() == 1,
+    Err(_) => false,
+  }
+}
+
+/// Which chars `State` and `Lines` treat as line breaks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum NewlineMode {
+  /// only `'\n'`
+  Lf,
+  /// `'\n'`, `'\r'` and `"\r\n"` as a single break
+  Crlf,
+  /// `Crlf` plus VT, FF, NEL, U+2028 and U+2029
+  Unicode,
+}
+
+impl Default for NewlineMode {
+  #[inline]
+  fn default() -> Self {
+    NewlineMode::Lf
+  }
+}
+
+impl NewlineMode {
+  #[inline]
+  pub fn is_line_break(&self, ch: char) -> bool {
+    match *self {
+      NewlineMode::Lf => ch == '\n',
+      NewlineMode::Crlf => ch == '\n' || ch == '\r',
+      NewlineMode::Unicode => matches!(
+        ch,
+        '\n' | '\r' | '\u{0B}' | '\u{0C}' | '\u{85}' | '\u{2028}' | '\u{2029}'
+      ),
+    }
+  }
+
+  #[inline]
+  pub fn is_crlf(&self, prev: char, ch: char) -> bool {
+    prev == '\r' && ch == '\n' && *self != NewlineMode::Lf
+  }
+}
+
+/// Compared and hashed by `index`, `row` and `col` alone, the modes and what
+/// was read last only decide how it moves on.
+#[derive(Debug, Clone)]
 pub struct State {
   index: usize,
   row: u64,
   col: u64,
+  column_mode: ColumnMode,
+  newline_mode: NewlineMode,
+  prev: Option<char>,
+  #[cfg(feature = "grapheme")]
+  cluster: Cluster,
 }
 
-unsafe impl Send for State {}
-unsafe impl Sync for State {}
-
 impl Default for State {
   #[inline]
   fn default() -> Self {
@@ -15,16 +195,63 @@ impl Default for State {
       index: 0usize,
       row: 1u64,
       col: 1u64,
+      column_mode: ColumnMode::default(),
+      newline_mode: NewlineMode::default(),
+      prev: None,
+      #[cfg(feature = "grapheme")]
+      cluster: Cluster::Other,
     }
   }
 }
 
+impl PartialEq for State {
+  #[inline]
+  fn eq(&self, other: &Self) -> bool {
+    self.key() == other.key()
+  }
+}
+
+impl Eq for State {}
+
+impl PartialOrd for State {
+  #[inline]
+  fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+    Some(self.cmp(other))
+  }
+}
+
+impl Ord for State {
+  #[inline]
+  fn cmp(&self, other: &Self) -> Ordering {
+    self.key().cmp(&other.key())
+  }
+}
+
+impl Hash for State {
+  #[inline]
+  fn hash<H: Hasher>(&self, state: &mut H) {
+    self.key().hash(state);
+  }
+}
+
 impl State {
   #[inline(always)]
   pub fn new() -> Self {
     Self::default()
   }
 
+  #[inline]
+  pub fn with_column_mode(mut self, column_mode: ColumnMode) -> Self {
+    self.column_mode = column_mode;
+    self
+  }
+
+  #[inline]
+  pub fn with_newline_mode(mut self, newline_mode: NewlineMode) -> Self {
+    self.newline_mode = newline_mode;
+    self
+  }
+
   #[inline(always)]
   pub fn index(&self) -> usize {
     self.index
@@ -37,16 +264,203 @@ impl State {
   pub fn col(&self) -> u64 {
     self.col
   }
+  #[inline(always)]
+  pub fn column_mode(&self) -> ColumnMode {
+    self.column_mode
+  }
+  #[inline(always)]
+  pub fn newline_mode(&self) -> NewlineMode {
+    self.newline_mode
+  }
+
+  /// The char read last, before the one at `index`.
+  #[inline(always)]
+  pub fn prev(&self) -> Option<char> {
+    self.prev
+  }
+
+  #[inline(always)]
+  fn key(&self) -> (usize, u64, u64) {
+    (self.index, self.row, self.col)
+  }
+
+  #[inline(always)]
+  pub fn is_line_break(&self, ch: char) -> bool {
+    self.newline_mode.is_line_break(ch)
+  }
+  #[inline]
+  pub(crate) fn is_crlf(&self, ch: char) -> bool {
+    match self.prev {
+      Some(prev) => self.newline_mode.is_crlf(prev, ch),
+      None => false,
+    }
+  }
+
+  /// A state with the same modes at the start of a line, after `prev` ended
+  /// the one before it.
+  #[inline]
+  pub(crate) fn line_start(&self, index: usize, row: u64, prev: char) -> State {
+    State {
+      index,
+      row,
+      col: 1,
+      column_mode: self.column_mode,
+      newline_mode: self.newline_mode,
+      prev: Some(prev),
+      #[cfg(feature = "grapheme")]
+      cluster: Cluster::Other,
+    }
+  }
 
   #[inline]
-  pub(crate) fn read(&mut self, is_newline: bool) {
-    if is_newline {
-      self.row += 1;
+  pub(crate) fn read(&mut self, ch: char) {
+    if self.is_line_break(ch) {
+      if !self.is_crlf(ch) {
+        self.row += 1;
+      }
       self.col = 1;
-    } else if self.index != 0 {
-      self.col += 1;
+      #[cfg(feature = "grapheme")]
+      {
+        self.cluster = Cluster::Other;
+      }
+    } else {
+      #[cfg(feature = "grapheme")]
+      let same_grapheme =
+        self.column_mode == ColumnMode::Grapheme && self.cluster.read(self.prev, ch);
+      #[cfg(not(feature = "grapheme"))]
+      let same_grapheme = false;
+
+      self.col = self.column_mode.next_col(self.col, ch, same_grapheme);
     }
 
     self.index += 1;
+    self.prev = Some(ch);
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use alloc::vec::Vec;
+
+  fn cols(column_mode: ColumnMode, string: &str) -> Vec<u64> {
+    let mut state = State::new().with_column_mode(column_mode);
+
+    string
+      .chars()
+      .map(|ch| {
+        state.read(ch);
+        state.col()
+      })
+      .collect()
+  }
+
+  fn rows(newline_mode: NewlineMode, string: &str) -> Vec<(u64, u64)> {
+    let mut state = State::new().with_newline_mode(newline_mode);
+
+    string
+      .chars()
+      .map(|ch| {
+        state.read(ch);
+        (state.row(), state.col())
+      })
+      .collect()
+  }
+
+  #[test]
+  fn test_eq_ignores_prev() {
+    let mut a = State::new();
+    let mut b = State::new().with_newline_mode(NewlineMode::Crlf);
+
+    a.read('a');
+    b.read('b');
+
+    assert_eq!(a, b);
+    assert_eq!(a.cmp(&b), Ordering::Equal);
+
+    b.read('x');
+    assert!(a < b);
+  }
+
+  #[test]
+  fn test_char_columns() {
+    assert_eq!(cols(ColumnMode::Char, "aé😀b"), [2, 3, 4, 5]);
+    assert_eq!(cols(ColumnMode::Char, "ab\ncd"), [2, 3, 1, 2, 3]);
+  }
+
+  #[test]
+  fn test_utf8_columns() {
+    assert_eq!(cols(ColumnMode::Utf8, "aé😀b"), [2, 4, 8, 9]);
+  }
+
+  #[test]
+  fn test_utf16_columns() {
+    assert_eq!(cols(ColumnMode::Utf16, "aé😀b"), [2, 3, 5, 6]);
+    assert_eq!(cols(ColumnMode::Utf16, "😀\n😀"), [3, 1, 3]);
+  }
+
+  #[test]
+  fn test_visual_columns() {
+    assert_eq!(cols(ColumnMode::Visual(4), "\tx"), [5, 6]);
+    assert_eq!(
+      cols(ColumnMode::Visual(4), "ab\tx\t\ty"),
+      [2, 3, 5, 6, 9, 13, 14]
+    );
+    assert_eq!(cols(ColumnMode::Visual(8), "a\n\t\tb"), [2, 1, 9, 17, 18]);
+  }
+
+  #[test]
+  fn test_lf_rows() {
+    assert_eq!(
+      rows(NewlineMode::Lf, "a\r\nb\rc"),
+      [(1, 2), (1, 3), (2, 1), (2, 2), (2, 3), (2, 4)]
+    );
+  }
+
+  #[test]
+  fn test_crlf_rows() {
+    assert_eq!(
+      rows(NewlineMode::Crlf, "a\r\nb\rc\n"),
+      [(1, 2), (2, 1), (2, 1), (2, 2), (3, 1), (3, 2), (4, 1)]
+    );
+    assert_eq!(rows(NewlineMode::Crlf, "\n\r"), [(2, 1), (3, 1)]);
+  }
+
+  #[test]
+  fn test_unicode_rows() {
+    assert_eq!(
+      rows(NewlineMode::Unicode, "a\u{2028}b\u{85}\r\n"),
+      [(1, 2), (2, 1), (2, 2), (3, 1), (4, 1), (4, 1)]
+    );
+    assert_eq!(rows(NewlineMode::Crlf, "a\u{2028}"), [(1, 2), (1, 3)]);
+  }
+
+  #[cfg(feature = "grapheme")]
+  #[test]
+  fn test_grapheme_columns() {
+    assert_eq!(cols(ColumnMode::Grapheme, "e\u{301}x"), [2, 2, 3]);
+    assert_eq!(cols(ColumnMode::Grapheme, "a\r\nb"), [2, 3, 1, 2]);
+  }
+
+  #[cfg(feature = "grapheme")]
+  #[test]
+  fn test_grapheme_flag_columns() {
+    assert_eq!(cols(ColumnMode::Grapheme, "🇺🇸🇬🇧x"), [2, 2, 3, 3, 4]);
+    assert_eq!(cols(ColumnMode::Grapheme, "🇺🇸🇬x"), [2, 2, 3, 4]);
+    assert_eq!(cols(ColumnMode::Grapheme, "🇺\n🇸🇬"), [2, 1, 2, 2]);
+  }
+
+  #[cfg(feature = "grapheme")]
+  #[test]
+  fn test_grapheme_zwj_columns() {
+    assert_eq!(
+      cols(ColumnMode::Grapheme, "👨\u{200D}👩\u{200D}👧x"),
+      [2, 2, 2, 2, 2, 3]
+    );
+    assert_eq!(
+      cols(ColumnMode::Grapheme, "👍\u{1F3FD}\u{200D}👍x"),
+      [2, 2, 2, 2, 3]
+    );
+    assert_eq!(cols(ColumnMode::Grapheme, "a\u{200D}👩x"), [2, 2, 3, 4]);
   }
 }
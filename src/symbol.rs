@@ -0,0 +1,18 @@
+use core::convert::TryFrom;
+
+/// An interned string, cheap to copy, compare and hash, resolved through the
+/// `SymbolTable` that made it.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Symbol(u32);
+
+impl Symbol {
+  #[inline(always)]
+  pub(crate) fn new(index: usize) -> Self {
+    Symbol(u32::try_from(index).expect("a symbol table holds at most u32::MAX symbols"))
+  }
+
+  #[inline(always)]
+  pub fn index(&self) -> usize {
+    self.0 as usize
+  }
+}
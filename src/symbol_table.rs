@@ -0,0 +1,154 @@
+use alloc::collections::BTreeMap;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use spin::RwLock;
+
+use super::Symbol;
+
+#[derive(Debug, Default)]
+struct Symbols {
+  ids: BTreeMap<Arc<str>, Symbol>,
+  strings: Vec<Arc<str>>,
+}
+
+/// Interns strings as `Symbol`s, storing every distinct string once. Safe to
+/// share between threads without `std`, behind a spin lock, and
+/// `Readers::symbols` holds the one readers use.
+#[derive(Debug, Default)]
+pub struct SymbolTable {
+  symbols: RwLock<Symbols>,
+}
+
+impl SymbolTable {
+  #[inline]
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  #[inline]
+  pub fn intern(&self, string: &str) -> Symbol {
+    if let Some(symbol) = self.get(string) {
+      return symbol;
+    }
+
+    let mut symbols = self.symbols.write();
+
+    if let Some(&symbol) = symbols.ids.get(string) {
+      return symbol;
+    }
+
+    let string: Arc<str> = Arc::from(string);
+    let symbol = Symbol::new(symbols.strings.len());
+
+    symbols.strings.push(string.clone());
+    symbols.ids.insert(string, symbol);
+    symbol
+  }
+
+  /// The symbol of `string` if it was interned.
+  #[inline]
+  pub fn get(&self, string: &str) -> Option<Symbol> {
+    self.symbols.read().ids.get(string).cloned()
+  }
+
+  #[inline]
+  pub fn resolve(&self, symbol: Symbol) -> Option<Arc<str>> {
+    self.symbols.read().strings.get(symbol.index()).cloned()
+  }
+
+  #[inline]
+  pub fn len(&self) -> usize {
+    self.symbols.read().strings.len()
+  }
+
+  #[inline]
+  pub fn is_empty(&self) -> bool {
+    self.len() == 0
+  }
+}
+
+#[cfg(test)]
+mod test {
+  #[cfg(feature = "std")]
+  use alloc::string::String;
+  #[cfg(feature = "std")]
+  use std::thread;
+
+  #[cfg(feature = "std")]
+  use super::super::readers::WhitespaceReader;
+  #[cfg(feature = "std")]
+  use super::super::{ReaderResult, SyncReadersBuilder, Token, TokenMeta};
+  use super::*;
+
+  #[test]
+  fn test_intern() {
+    let symbols = SymbolTable::new();
+    let a = symbols.intern("a");
+    let b = symbols.intern("b");
+
+    assert_ne!(a, b);
+    assert_eq!(symbols.intern("a"), a);
+    assert_eq!(symbols.get("b"), Some(b));
+    assert_eq!(symbols.get("c"), None);
+    assert_eq!(symbols.resolve(b).as_deref(), Some("b"));
+    assert_eq!(symbols.len(), 2);
+  }
+
+  #[cfg(feature = "std")]
+  #[test]
+  fn test_readers_symbols() {
+    let readers = SyncReadersBuilder::<Token<Symbol>, ()>::new()
+      .add(WhitespaceReader::new())
+      .add_fn(|readers, input, current, next| {
+        let mut string = String::new();
+
+        while let Some(ch) = input.peek(next, 0) {
+          if !ch.is_alphabetic() {
+            break;
+          }
+          input.read(next);
+          string.push(ch);
+        }
+
+        if string.is_empty() {
+          ReaderResult::None
+        } else {
+          ReaderResult::Some(Token::new(
+            TokenMeta::new_state_meta(current, next),
+            readers.symbols().intern(&string),
+          ))
+        }
+      })
+      .build();
+
+    let symbols: Vec<Vec<Symbol>> = thread::scope(|scope| {
+      let handles: Vec<_> = (0..4)
+        .map(|_| {
+          scope.spawn(|| {
+            readers
+              .readers()
+              .read("foo bar foo baz".chars())
+              .filter_map(Result::ok)
+              .map(|token| *token.value())
+              .collect()
+          })
+        })
+        .collect();
+
+      handles
+        .into_iter()
+        .map(|handle| handle.join().unwrap())
+        .collect()
+    });
+
+    assert_eq!(readers.symbols().len(), 3);
+    for symbols in &symbols {
+      assert_eq!(symbols.len(), 4);
+      assert_eq!(symbols[0], symbols[2]);
+      assert_eq!(
+        readers.symbols().resolve(symbols[3]).as_deref(),
+        Some("baz")
+      );
+    }
+  }
+}
@@ -0,0 +1,130 @@
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+
+use super::SymbolTable;
+use super::{Attempt, Observer, Reader, Readers, ShadowWarning, SyncReadersBuilder};
+
+/// Readers that are all `Send + Sync`, so one `SyncReaders` can be shared by
+/// threads whatever `T` and `E` are. Built by `SyncReadersBuilder`, each
+/// thread reads with the `Readers` from `SyncReaders::readers`, which share
+/// the readers, symbols and observer.
+///
+/// ```
+/// fn shared<R: Send + Sync>(_: &R) {}
+///
+/// let readers = lexer::SyncReadersBuilder::<std::rc::Rc<str>, ()>::new().build();
+/// shared(&readers);
+///
+/// std::thread::scope(|scope| {
+///   scope.spawn(|| readers.readers().read("".chars()).count());
+/// });
+/// ```
+///
+/// Readers holding an `Rc` or a `Cell` are rejected, see
+/// `tests/compile_fail`.
+pub struct SyncReaders<T, E> {
+  readers: Vec<Arc<dyn Reader<T, E> + Send + Sync>>,
+  symbols: Arc<SymbolTable>,
+  observer: Option<Arc<Observer>>,
+  warnings: Vec<ShadowWarning>,
+}
+
+impl<T, E> From<SyncReadersBuilder<T, E>> for SyncReaders<T, E> {
+  #[inline]
+  fn from(sync_readers_builder: SyncReadersBuilder<T, E>) -> SyncReaders<T, E> {
+    sync_readers_builder.build()
+  }
+}
+
+impl<T, E> From<SyncReaders<T, E>> for Readers<T, E> {
+  #[inline]
+  fn from(sync_readers: SyncReaders<T, E>) -> Readers<T, E> {
+    sync_readers.readers()
+  }
+}
+
+impl<T, E> Default for SyncReaders<T, E> {
+  #[inline]
+  fn default() -> Self {
+    SyncReaders::from_arcs(Vec::new())
+  }
+}
+
+impl<T, E> SyncReaders<T, E> {
+  #[inline]
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  #[inline]
+  pub(crate) fn from_arcs(readers: Vec<Arc<dyn Reader<T, E> + Send + Sync>>) -> Self {
+    SyncReaders {
+      readers,
+      symbols: Arc::default(),
+      observer: None,
+      warnings: Vec::new(),
+    }
+  }
+
+  /// `Readers` sharing these readers, for one thread to read with.
+  #[inline]
+  pub fn readers(&self) -> Readers<T, E> {
+    Readers {
+      readers: self
+        .readers
+        .iter()
+        .map(|reader| Arc::clone(reader) as Arc<dyn Reader<T, E>>)
+        .collect(),
+      symbols: Arc::clone(&self.symbols),
+      observer: self.observer.clone(),
+      warnings: self.warnings.clone(),
+    }
+  }
+
+  /// See `Readers::symbols`.
+  #[inline(always)]
+  pub fn symbols(&self) -> &SymbolTable {
+    &self.symbols
+  }
+
+  /// Interns into `symbols` instead, see `Readers::with_symbols`.
+  #[inline]
+  pub fn with_symbols(mut self, symbols: Arc<SymbolTable>) -> Self {
+    self.symbols = symbols;
+    self
+  }
+
+  /// Calls `observer` with every reader attempt, see
+  /// `Readers::with_observer`.
+  #[inline]
+  pub fn with_observer<F>(mut self, observer: F) -> Self
+  where
+    F: 'static + Fn(&Attempt) + Send + Sync,
+  {
+    self.observer = Some(Arc::new(observer));
+    self
+  }
+
+  /// See `Readers::warnings`.
+  #[inline(always)]
+  pub fn warnings(&self) -> &[ShadowWarning] {
+    &self.warnings
+  }
+
+  #[inline(always)]
+  pub(crate) fn set_warnings(&mut self, warnings: Vec<ShadowWarning>) {
+    self.warnings = warnings;
+  }
+
+  /// The index of the first reader named `name`.
+  #[inline]
+  pub fn position(&self, name: &str) -> Option<usize> {
+    self.readers.iter().position(|reader| reader.name() == name)
+  }
+
+  /// The names of the readers, in the order they are tried.
+  #[inline]
+  pub fn names(&self) -> impl Iterator<Item = &str> {
+    self.readers.iter().map(|reader| reader.name())
+  }
+}
@@ -0,0 +1,143 @@
+use alloc::string::String;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+
+use super::readers_builder::{shadow_warnings, Entries};
+use super::{Input, Named, Reader, ReaderResult, Readers, ShadowWarning, State, SyncReaders};
+
+/// A `ReadersBuilder` that only takes `Send + Sync` readers, to build
+/// `SyncReaders`.
+pub struct SyncReadersBuilder<T, E>(Entries<T, E, dyn Reader<T, E> + Send + Sync>);
+
+impl<T, E> Default for SyncReadersBuilder<T, E> {
+  #[inline]
+  fn default() -> Self {
+    SyncReadersBuilder(Entries::default())
+  }
+}
+
+impl<T, E> SyncReadersBuilder<T, E> {
+  #[inline]
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  #[inline]
+  #[allow(clippy::should_implement_trait)]
+  pub fn add<R: 'static + Reader<T, E> + Send + Sync>(self, reader: R) -> Self {
+    SyncReadersBuilder(self.0.add(Arc::new(reader)))
+  }
+
+  #[inline]
+  pub fn add_fn<F>(self, f: F) -> Self
+  where
+    F: 'static
+      + Send
+      + Sync
+      + Fn(&Readers<T, E>, &mut dyn Input, &State, &mut State) -> ReaderResult<T, E>,
+  {
+    self.add(f)
+  }
+
+  /// See `ReadersBuilder::add_named`.
+  #[inline]
+  pub fn add_named<S, R>(self, name: S, reader: R) -> Self
+  where
+    S: Into<String>,
+    R: 'static + Reader<T, E> + Send + Sync,
+  {
+    self.add(Named::new(name, reader))
+  }
+
+  /// See `ReadersBuilder::priority`.
+  #[inline]
+  pub fn priority(self, priority: i32) -> Self {
+    SyncReadersBuilder(self.0.priority(priority))
+  }
+
+  /// See `ReadersBuilder::samples`.
+  #[inline]
+  pub fn samples<I, S>(self, samples: I) -> Self
+  where
+    I: IntoIterator<Item = S>,
+    S: Into<String>,
+  {
+    SyncReadersBuilder(self.0.samples(samples))
+  }
+
+  /// See `ReadersBuilder::check_shadows`.
+  #[inline]
+  pub fn check_shadows(self) -> Self {
+    SyncReadersBuilder(self.0.check_shadows())
+  }
+
+  /// See `ReadersBuilder::insert_before`.
+  #[inline]
+  pub fn insert_before<R>(self, name: &str, reader: R) -> Self
+  where
+    R: 'static + Reader<T, E> + Send + Sync,
+  {
+    SyncReadersBuilder(self.0.insert_before(name, Arc::new(reader)))
+  }
+
+  /// See `ReadersBuilder::insert_after`.
+  #[inline]
+  pub fn insert_after<R>(self, name: &str, reader: R) -> Self
+  where
+    R: 'static + Reader<T, E> + Send + Sync,
+  {
+    SyncReadersBuilder(self.0.insert_after(name, Arc::new(reader)))
+  }
+
+  /// See `ReadersBuilder::replace`.
+  #[inline]
+  pub fn replace<R>(self, name: &str, reader: R) -> Self
+  where
+    R: 'static + Reader<T, E> + Send + Sync,
+  {
+    let index = self.0.expect_position(name);
+    SyncReadersBuilder(self.0.replace(index, Arc::new(Named::new(name, reader))))
+  }
+
+  /// See `ReadersBuilder::remove`.
+  #[inline]
+  pub fn remove(self, name: &str) -> Self {
+    SyncReadersBuilder(self.0.remove(name))
+  }
+
+  #[inline]
+  pub fn position(&self, name: &str) -> Option<usize> {
+    self.0.position(name)
+  }
+
+  #[inline]
+  pub fn names(&self) -> impl Iterator<Item = &str> {
+    self.0.names()
+  }
+
+  /// See `ReadersBuilder::build`, readers are probed for shadowing through
+  /// `SyncReaders::readers`.
+  #[inline]
+  pub fn build(self) -> SyncReaders<T, E> {
+    let (readers, samples) = self.0.build();
+    let mut readers = SyncReaders::from_arcs(readers);
+
+    if let Some(samples) = samples {
+      let warnings = shadow_warnings(&readers.readers(), &samples);
+      readers.set_warnings(warnings);
+    }
+    readers
+  }
+
+  /// See `ReadersBuilder::build_checked`.
+  #[inline]
+  pub fn build_checked(self) -> Result<SyncReaders<T, E>, Vec<ShadowWarning>> {
+    let readers = self.check_shadows().build();
+
+    if readers.warnings().is_empty() {
+      Ok(readers)
+    } else {
+      Err(readers.warnings().to_vec())
+    }
+  }
+}
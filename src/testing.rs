@@ -0,0 +1,477 @@
+//! Checks that hold for the tokens of any `Readers`, plus a seeded input
+//! generator to run them on many inputs, for property tests and fuzz targets,
+//! and snapshots of token streams for golden tests.
+
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+use core::fmt::Write;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::Path;
+use std::{env, fs};
+
+use super::{Readers, Token, TokenError, TokenMeta};
+
+/// An invariant the tokens of an input broke, with the index of the first
+/// token that broke it.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Violation {
+  /// the token does not end after it starts
+  Empty(usize, TokenMeta),
+  /// the token starts before the previous one ends
+  Overlap(usize, TokenMeta),
+  /// the token ends after the input
+  OutOfBounds(usize, TokenMeta),
+  /// with lossless trivia, input between the previous token and this one, or
+  /// after the last token, that no token covers
+  Gap(usize, u64, u64),
+  /// a reader panicked reading the token, with the panic message
+  Panic(usize, String),
+}
+
+impl fmt::Display for Violation {
+  #[inline]
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match *self {
+      Violation::Empty(index, ref meta) => write!(
+        f,
+        "token {} is empty at {}..{}",
+        index,
+        meta.index_start(),
+        meta.index_end()
+      ),
+      Violation::Overlap(index, ref meta) => write!(
+        f,
+        "token {} at {}..{} overlaps the token before it",
+        index,
+        meta.index_start(),
+        meta.index_end()
+      ),
+      Violation::OutOfBounds(index, ref meta) => write!(
+        f,
+        "token {} at {}..{} ends after the input",
+        index,
+        meta.index_start(),
+        meta.index_end()
+      ),
+      Violation::Gap(index, start, end) => write!(
+        f,
+        "no token covers {}..{} before token {}",
+        start, end, index
+      ),
+      Violation::Panic(index, ref message) => {
+        write!(f, "a reader panicked reading token {}: {}", index, message)
+      }
+    }
+  }
+}
+
+/// Lexes `input` and checks that every token and error spans some input,
+/// that spans are in order without overlapping and stay within the input,
+/// returning how many tokens and errors were read. With `lossless`, for
+/// readers that return their trivia as tokens, the spans must also cover the
+/// input exactly. A reader panicking is reported as a `Violation::Panic`.
+#[inline]
+pub fn check<V, E>(
+  readers: &Readers<Token<V>, TokenError<E>>,
+  input: &str,
+  lossless: bool,
+) -> Result<usize, Violation> {
+  let mut count = 0;
+
+  match panic::catch_unwind(AssertUnwindSafe(|| {
+    check_tokens(readers, input, lossless, &mut count)
+  })) {
+    Ok(result) => result,
+    Err(payload) => {
+      let message = match payload.downcast_ref::<&str>() {
+        Some(message) => message.to_string(),
+        None => match payload.downcast_ref::<String>() {
+          Some(message) => message.clone(),
+          None => "unknown panic".into(),
+        },
+      };
+      Err(Violation::Panic(count, message))
+    }
+  }
+}
+
+#[inline]
+fn check_tokens<V, E>(
+  readers: &Readers<Token<V>, TokenError<E>>,
+  input: &str,
+  lossless: bool,
+  count: &mut usize,
+) -> Result<usize, Violation> {
+  let len = input.chars().count() as u64;
+  let mut end = 0;
+
+  for (index, result) in readers.read(input.chars()).enumerate() {
+    let meta = match result {
+      Ok(ref token) => token.meta(),
+      Err(ref error) => error.meta(),
+    };
+
+    if meta.index_end() <= meta.index_start() {
+      return Err(Violation::Empty(index, meta.clone()));
+    }
+    if meta.index_start() < end {
+      return Err(Violation::Overlap(index, meta.clone()));
+    }
+    if meta.index_end() > len {
+      return Err(Violation::OutOfBounds(index, meta.clone()));
+    }
+    if lossless && meta.index_start() != end {
+      return Err(Violation::Gap(index, end, meta.index_start()));
+    }
+
+    end = meta.index_end();
+    *count += 1;
+  }
+
+  if lossless && end != len {
+    return Err(Violation::Gap(*count, end, len));
+  }
+  Ok(*count)
+}
+
+/// Runs `check` on `runs` inputs made by `random_input` from `seed`,
+/// panicking with the failing input and its seed.
+#[inline]
+pub fn check_random<V, E>(
+  readers: &Readers<Token<V>, TokenError<E>>,
+  fragments: &[&str],
+  seed: u64,
+  runs: usize,
+  lossless: bool,
+) {
+  let mut rng = Rng::new(seed);
+
+  for _ in 0..runs {
+    let input_seed = rng.next_u64();
+    let input = random_input(&mut Rng::new(input_seed), fragments, 64);
+
+    if let Err(violation) = check(readers, &input, lossless) {
+      panic!(
+        "{} in {:?}, generated from seed {}",
+        violation, input, input_seed
+      );
+    }
+  }
+}
+
+/// A small xorshift generator, so failures reproduce from their seed.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Rng(u64);
+
+impl Rng {
+  #[inline]
+  pub fn new(seed: u64) -> Self {
+    // xorshift never leaves zero
+    Rng(seed ^ 0x9E37_79B9_7F4A_7C15)
+  }
+
+  #[inline]
+  pub fn next_u64(&mut self) -> u64 {
+    self.0 ^= self.0 << 13;
+    self.0 ^= self.0 >> 7;
+    self.0 ^= self.0 << 17;
+    self.0
+  }
+
+  /// A number below `max`, which must not be zero.
+  #[inline]
+  pub fn below(&mut self, max: usize) -> usize {
+    (self.next_u64() % max as u64) as usize
+  }
+}
+
+/// Joins up to `max_fragments` randomly picked `fragments`, which should hold
+/// the pieces of the grammar under test along with some odd input.
+#[inline]
+pub fn random_input(rng: &mut Rng, fragments: &[&str], max_fragments: usize) -> String {
+  let mut input = String::new();
+
+  if fragments.is_empty() {
+    return input;
+  }
+  for _ in 0..rng.below(max_fragments + 1) {
+    input.push_str(fragments[rng.below(fragments.len())]);
+  }
+  input
+}
+
+/// Renders the tokens and errors `readers` read from `source` one per line as
+/// `kind "lexeme" @line:col-line:col`, with `kind` giving the kind of each
+/// value and errors shown as `error(message)`. Lexemes are escaped like Rust
+/// strings, so the output stays on one line per token.
+#[inline]
+pub fn render<V, E, F, K>(
+  readers: &Readers<Token<V>, TokenError<E>>,
+  source: &str,
+  mut kind: F,
+) -> String
+where
+  F: FnMut(&V) -> K,
+  K: fmt::Display,
+  E: fmt::Display,
+{
+  let offsets: Vec<usize> = source
+    .char_indices()
+    .map(|(offset, _)| offset)
+    .chain(Some(source.len()))
+    .collect();
+  let lexeme = |meta: &TokenMeta| {
+    let start = offsets.len().min(meta.index_start() as usize + 1) - 1;
+    let end = offsets.len().min(meta.index_end() as usize + 1) - 1;
+    &source[offsets[start]..offsets[end.max(start)]]
+  };
+  let mut output = String::new();
+
+  for result in readers.read(source.chars()) {
+    let meta = match result {
+      Ok(ref token) => {
+        let _ = write!(output, "{}", kind(token.value()));
+        token.meta()
+      }
+      Err(ref error) => {
+        let _ = write!(output, "error({})", error.error());
+        error.meta()
+      }
+    };
+
+    let _ = writeln!(
+      output,
+      " {:?} @{}:{}-{}:{}",
+      lexeme(meta),
+      meta.row_start(),
+      meta.col_start(),
+      meta.row_end(),
+      meta.col_end()
+    );
+  }
+
+  output
+}
+
+/// A line diff of `expected` and `actual`, lines only in `expected` start
+/// with `-`, lines only in `actual` with `+`.
+#[inline]
+pub fn diff(expected: &str, actual: &str) -> String {
+  let expected: Vec<&str> = expected.lines().collect();
+  let actual: Vec<&str> = actual.lines().collect();
+  let width = actual.len() + 1;
+  // common[i * width + j] is the longest common subsequence of
+  // expected[i..] and actual[j..]
+  let mut common = vec![0usize; (expected.len() + 1) * width];
+
+  for i in (0..expected.len()).rev() {
+    for j in (0..actual.len()).rev() {
+      common[i * width + j] = if expected[i] == actual[j] {
+        common[(i + 1) * width + j + 1] + 1
+      } else {
+        common[(i + 1) * width + j].max(common[i * width + j + 1])
+      };
+    }
+  }
+
+  let mut output = String::new();
+  let (mut i, mut j) = (0, 0);
+
+  while i < expected.len() || j < actual.len() {
+    if i < expected.len() && j < actual.len() && expected[i] == actual[j] {
+      let _ = writeln!(output, "  {}", expected[i]);
+      i += 1;
+      j += 1;
+    } else if i < expected.len()
+      && (j == actual.len() || common[(i + 1) * width + j] >= common[i * width + j + 1])
+    {
+      let _ = writeln!(output, "- {}", expected[i]);
+      i += 1;
+    } else {
+      let _ = writeln!(output, "+ {}", actual[j]);
+      j += 1;
+    }
+  }
+
+  output
+}
+
+/// Set to write snapshots instead of comparing against them.
+pub const UPDATE_SNAPSHOTS: &str = "LEXER_UPDATE_SNAPSHOTS";
+
+/// Compares `actual` with the snapshot at `path`, panicking with a diff when
+/// they differ or when the snapshot can not be read. Snapshots are only
+/// written when the `LEXER_UPDATE_SNAPSHOTS` environment variable is set.
+#[inline]
+pub fn assert_snapshot<P>(path: P, actual: &str)
+where
+  P: AsRef<Path>,
+{
+  let update = env::var_os(UPDATE_SNAPSHOTS).is_some_and(|value| value != "0");
+  check_snapshot(path.as_ref(), actual, update);
+}
+
+#[inline]
+fn check_snapshot(path: &Path, actual: &str, update: bool) {
+  if update {
+    if let Some(parent) = path.parent() {
+      fs::create_dir_all(parent).expect("failed to create the snapshot directory");
+    }
+    fs::write(path, actual).expect("failed to write the snapshot");
+    return;
+  }
+
+  match fs::read_to_string(path) {
+    Ok(expected) => {
+      let expected = expected.replace("\r\n", "\n");
+
+      if expected != actual {
+        panic!(
+          "snapshot {} does not match, set {}=1 to update it\n{}",
+          path.display(),
+          UPDATE_SNAPSHOTS,
+          diff(&expected, actual)
+        );
+      }
+    }
+    Err(error) => panic!(
+      "snapshot {} could not be read: {}, set {}=1 to write it",
+      path.display(),
+      error,
+      UPDATE_SNAPSHOTS
+    ),
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use alloc::string::String;
+
+  use super::super::readers::{ReadError, StringReader, WhitespaceReader};
+  use super::super::{char_class, ReaderResult, ReadersBuilder};
+  use super::*;
+
+  type Readers = super::Readers<Token<String>, TokenError<ReadError>>;
+
+  fn readers() -> Readers {
+    ReadersBuilder::new()
+      .add(WhitespaceReader::new())
+      .add(StringReader::new(
+        |string, meta| Token::new(meta, string),
+        |error, meta| TokenError::new(meta, error),
+      ))
+      .add(char_class(char::is_alphanumeric, |string, meta| {
+        Token::new(meta, string)
+      }))
+      .add(char_class(
+        |ch: char| !ch.is_alphanumeric() && !ch.is_whitespace() && ch != '"',
+        |string, meta| Token::new(meta, string),
+      ))
+      .build()
+  }
+
+  #[test]
+  fn test_check() {
+    let readers = readers();
+
+    assert_eq!(check(&readers, "a + \"b\"", false), Ok(3));
+    assert_eq!(check(&readers, "a b", true), Err(Violation::Gap(1, 1, 2)));
+    check_random(
+      &readers,
+      &["a", "1", " ", "\n", "\"", "\\", "+", "é", "😀"],
+      0,
+      200,
+      false,
+    );
+  }
+
+  #[test]
+  fn test_check_overlap() {
+    let readers: Readers = ReadersBuilder::new()
+      .add_fn(|_, input, _, next| match input.read(next) {
+        Some(_) => ReaderResult::Some(Token::new(
+          TokenMeta::new(0, next.index() as u64, 1, 1, 1, 1),
+          String::new(),
+        )),
+        None => ReaderResult::None,
+      })
+      .build();
+
+    assert_eq!(
+      check(&readers, "ab", false),
+      Err(Violation::Overlap(1, TokenMeta::new(0, 2, 1, 1, 1, 1)))
+    );
+  }
+
+  #[test]
+  fn test_check_panic() {
+    let readers: Readers = ReadersBuilder::new()
+      .add_fn(|_, input, current, next| match input.read(next) {
+        Some('!') => panic!("bad char"),
+        Some(ch) => ReaderResult::Some(Token::new(
+          TokenMeta::new_state_meta(current, next),
+          ch.into(),
+        )),
+        None => ReaderResult::None,
+      })
+      .build();
+
+    assert_eq!(
+      check(&readers, "ab!", false),
+      Err(Violation::Panic(2, "bad char".into()))
+    );
+  }
+
+  #[test]
+  fn test_random_input() {
+    let fragments = ["a", "b", "c"];
+    let input = random_input(&mut Rng::new(7), &fragments, 16);
+
+    assert_eq!(random_input(&mut Rng::new(7), &fragments, 16), input);
+    assert!(input.len() <= 16);
+    assert!(random_input(&mut Rng::new(7), &[], 16).is_empty());
+  }
+
+  #[test]
+  fn test_render() {
+    assert_eq!(
+      render(&readers(), "ab +\n\"c\\q\"", |value| value.len()),
+      concat!(
+        "2 \"ab\" @1:1-1:3\n",
+        "1 \"+\" @1:4-1:5\n",
+        "error(invalid escape) \"\\\\q\" @2:3-2:5\n",
+      )
+    );
+  }
+
+  #[test]
+  fn test_diff() {
+    assert_eq!(
+      diff("a\nb\nc\n", "a\nx\nc\nd\n"),
+      "  a\n- b\n+ x\n  c\n+ d\n"
+    );
+    assert_eq!(diff("a\n", "a\n"), "  a\n");
+  }
+
+  #[test]
+  fn test_assert_snapshot() {
+    let path = std::env::temp_dir()
+      .join("lexer-snapshots")
+      .join(alloc::format!("{}", std::process::id()))
+      .join("test.snap");
+
+    let missing = std::panic::catch_unwind(|| check_snapshot(&path, "a\n", false));
+    assert!(missing.is_err());
+    assert!(!path.exists());
+
+    check_snapshot(&path, "a\n", true);
+    check_snapshot(&path, "a\n", false);
+
+    let changed = std::panic::catch_unwind(|| check_snapshot(&path, "b\n", false));
+    let _ = std::fs::remove_dir_all(path.parent().unwrap());
+
+    assert!(changed.is_err());
+  }
+}
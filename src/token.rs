@@ -6,16 +6,10 @@ pub struct Token<T> {
   value: T,
 }
 
-unsafe impl<T> Send for Token<T> where T: Send {}
-unsafe impl<T> Sync for Token<T> where T: Sync {}
-
 impl<T> Token<T> {
   #[inline(always)]
   pub fn new(meta: TokenMeta, value: T) -> Self {
-    Token {
-      meta: meta,
-      value: value,
-    }
+    Token { meta, value }
   }
 
   #[inline(always)]
@@ -34,4 +28,13 @@ impl<T> Token<T> {
   pub fn into_value(self) -> T {
     self.value
   }
+
+  /// The token with its value mapped by `f`, keeping its meta.
+  #[inline]
+  pub fn map<U, F>(self, f: F) -> Token<U>
+  where
+    F: FnOnce(T) -> U,
+  {
+    Token::new(self.meta, f(self.value))
+  }
 }
@@ -0,0 +1,331 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+use core::mem;
+use core::ops::Range;
+use core::str::Chars;
+
+use super::{State, Token, TokenBufferIter, TokenMeta};
+
+/// Stores tokens as parallel arrays of kinds, start offsets and lengths, 8
+/// bytes plus the kind per token where a `Token` spends 48 bytes on its
+/// `TokenMeta` alone. Rows and columns are recomputed from the source through
+/// an index of line starts, so every lookup takes the source the tokens were
+/// read from. Offsets are char indices and must fit in a `u32`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenBuffer<K> {
+  kinds: Vec<K>,
+  starts: Vec<u32>,
+  lens: Vec<u32>,
+  line_indices: Vec<u32>,
+  line_offsets: Vec<u32>,
+  state: State,
+}
+
+impl<K> TokenBuffer<K> {
+  #[inline]
+  pub fn new(source: &str) -> Self {
+    Self::new_with_state(source, State::new())
+  }
+
+  /// A buffer for tokens read from `source` starting at `state`, which also
+  /// sets the column and newline modes positions are recomputed with.
+  #[inline]
+  pub fn new_with_state(source: &str, state: State) -> Self {
+    let mut line_indices = vec![to_u32(state.index())];
+    let mut line_offsets = vec![0];
+    let mut next = state.clone();
+
+    for (offset, ch) in source.char_indices() {
+      let row = next.row();
+      next.read(ch);
+
+      if next.row() != row {
+        line_indices.push(to_u32(next.index()));
+        line_offsets.push(to_u32(offset + ch.len_utf8()));
+      }
+    }
+
+    TokenBuffer {
+      kinds: Vec::new(),
+      starts: Vec::new(),
+      lens: Vec::new(),
+      line_indices,
+      line_offsets,
+      state,
+    }
+  }
+
+  #[inline]
+  pub fn push(&mut self, kind: K, meta: &TokenMeta) {
+    self.kinds.push(kind);
+    self.starts.push(to_u32(meta.index_start() as usize));
+    self.lens.push(to_u32(meta.len() as usize));
+  }
+
+  #[inline]
+  pub fn len(&self) -> usize {
+    self.kinds.len()
+  }
+
+  #[inline]
+  pub fn is_empty(&self) -> bool {
+    self.kinds.is_empty()
+  }
+
+  #[inline]
+  pub fn kinds(&self) -> &[K] {
+    &self.kinds
+  }
+
+  #[inline]
+  pub fn kind(&self, index: usize) -> Option<&K> {
+    self.kinds.get(index)
+  }
+
+  /// The char indices the token at `index` spans.
+  #[inline]
+  pub fn range(&self, index: usize) -> Option<Range<usize>> {
+    let start = *self.starts.get(index)? as usize;
+    Some(start..start + self.lens[index] as usize)
+  }
+
+  #[inline]
+  pub fn line_count(&self) -> usize {
+    self.line_indices.len()
+  }
+
+  /// Recomputes the full `TokenMeta` of the token at `index`.
+  #[inline]
+  pub fn meta(&self, source: &str, index: usize) -> Option<TokenMeta> {
+    let range = self.range(index)?;
+    let mut cursor = self.cursor(source, range.start);
+    cursor.read_token(range).map(|(meta, _)| meta)
+  }
+
+  #[inline]
+  pub fn lexeme<'s>(&self, source: &'s str, index: usize) -> Option<&'s str> {
+    let range = self.range(index)?;
+    let mut cursor = self.cursor(source, range.start);
+    cursor.read_token(range).map(|(_, lexeme)| lexeme)
+  }
+
+  /// Rebuilds the tokens one at a time, their values made by `to_value` from
+  /// the kind and the lexeme.
+  #[inline]
+  pub fn tokens<'a, 's, T, F>(
+    &'a self,
+    source: &'s str,
+    to_value: F,
+  ) -> TokenBufferIter<'a, 's, K, F>
+  where
+    F: FnMut(&K, &'s str) -> T,
+  {
+    TokenBufferIter::new(self, source, to_value)
+  }
+
+  /// Bytes allocated for the tokens and the line index.
+  #[inline]
+  pub fn heap_size(&self) -> usize {
+    self.kinds.capacity() * mem::size_of::<K>()
+      + (self.starts.capacity()
+        + self.lens.capacity()
+        + self.line_indices.capacity()
+        + self.line_offsets.capacity())
+        * mem::size_of::<u32>()
+  }
+
+  #[inline]
+  pub fn shrink_to_fit(&mut self) {
+    self.kinds.shrink_to_fit();
+    self.starts.shrink_to_fit();
+    self.lens.shrink_to_fit();
+    self.line_indices.shrink_to_fit();
+    self.line_offsets.shrink_to_fit();
+  }
+
+  /// A cursor at the start of the line holding the char at `index`.
+  #[inline]
+  pub(crate) fn cursor<'s>(&self, source: &'s str, index: usize) -> Cursor<'s> {
+    let line = self
+      .line_indices
+      .partition_point(|&line_index| line_index as usize <= index)
+      .max(1)
+      - 1;
+    let offset = self.line_offsets[line] as usize;
+    let state = if line == 0 {
+      self.state.clone()
+    } else {
+      self.state.line_start(
+        self.line_indices[line] as usize,
+        self.state.row() + line as u64,
+        source[..offset].chars().next_back().unwrap_or('\n'),
+      )
+    };
+
+    Cursor {
+      state,
+      chars: source[offset..].chars(),
+      offset,
+      source,
+    }
+  }
+
+  #[inline(always)]
+  pub(crate) fn start(&self, index: usize) -> usize {
+    self.starts[index] as usize
+  }
+}
+
+impl<K> Extend<Token<K>> for TokenBuffer<K> {
+  #[inline]
+  fn extend<I>(&mut self, tokens: I)
+  where
+    I: IntoIterator<Item = Token<K>>,
+  {
+    for token in tokens {
+      let meta = token.meta().clone();
+      self.push(token.into_value(), &meta);
+    }
+  }
+}
+
+#[inline]
+fn to_u32(value: usize) -> u32 {
+  u32::try_from(value).expect("token buffer offsets must fit in a u32")
+}
+
+/// Walks the source from a known state, recomputing positions as it goes.
+pub(crate) struct Cursor<'s> {
+  state: State,
+  chars: Chars<'s>,
+  offset: usize,
+  source: &'s str,
+}
+
+impl<'s> Cursor<'s> {
+  #[inline(always)]
+  pub(crate) fn index(&self) -> usize {
+    self.state.index()
+  }
+
+  #[inline]
+  fn read_to(&mut self, index: usize) -> Option<()> {
+    while self.state.index() < index {
+      let ch = self.chars.next()?;
+      self.offset += ch.len_utf8();
+      self.state.read(ch);
+    }
+    Some(())
+  }
+
+  /// Reads up to and over `range`, returning its meta and text.
+  #[inline]
+  pub(crate) fn read_token(&mut self, range: Range<usize>) -> Option<(TokenMeta, &'s str)> {
+    self.read_to(range.start)?;
+
+    let current = self.state.clone();
+    let start = self.offset;
+
+    self.read_to(range.end)?;
+
+    let meta = if range.is_empty() {
+      TokenMeta::new_point_meta(&current)
+    } else {
+      TokenMeta::new_state_meta(&current, &self.state)
+    };
+    Some((meta, &self.source[start..self.offset]))
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use alloc::string::String;
+
+  use super::super::readers::WhitespaceReader;
+  use super::super::{char_class, ColumnMode, NewlineMode, ReadersBuilder};
+  use super::*;
+
+  fn lex(source: &str, state: State) -> (Vec<Token<String>>, TokenBuffer<usize>) {
+    let readers = ReadersBuilder::<Token<String>, ()>::new()
+      .add(WhitespaceReader::new())
+      .add(char_class(char::is_alphanumeric, |string, meta| {
+        Token::new(meta, string)
+      }))
+      .add(char_class(
+        |ch| !ch.is_alphanumeric() && !ch.is_whitespace(),
+        |string, meta| Token::new(meta, string),
+      ))
+      .build();
+    let tokens: Vec<_> = readers
+      .read_with_state(source.chars(), state.clone())
+      .map(Result::unwrap)
+      .collect();
+    let mut buffer = TokenBuffer::new_with_state(source, state);
+
+    buffer.extend(
+      tokens
+        .iter()
+        .map(|token| Token::new(token.meta().clone(), token.value().len())),
+    );
+    (tokens, buffer)
+  }
+
+  fn assert_rebuilds(source: &str, state: State) {
+    let (tokens, buffer) = lex(source, state);
+    let rebuilt: Vec<_> = buffer
+      .tokens(source, |_, lexeme| String::from(lexeme))
+      .collect();
+
+    assert_eq!(rebuilt, tokens);
+    for (index, token) in tokens.iter().enumerate() {
+      assert_eq!(buffer.meta(source, index).as_ref(), Some(token.meta()));
+      assert_eq!(buffer.lexeme(source, index), Some(token.value().as_str()));
+    }
+  }
+
+  #[test]
+  fn test_token_buffer() {
+    let source = "fn é(a) {\n  b + 10\n}\n";
+    let (_, buffer) = lex(source, State::new());
+
+    assert_eq!(buffer.len(), 10);
+    assert_eq!(buffer.line_count(), 4);
+    assert_eq!(buffer.kinds()[8], 2);
+    assert_eq!(buffer.range(8), Some(16..18));
+    assert_eq!(buffer.lexeme(source, 8), Some("10"));
+    assert_eq!(
+      buffer.meta(source, 8),
+      Some(TokenMeta::new(16, 18, 7, 9, 2, 2))
+    );
+    assert_eq!(buffer.meta(source, 10), None);
+    assert_rebuilds(source, State::new());
+  }
+
+  #[test]
+  fn test_shrink_to_fit() {
+    let (_, mut buffer) = lex("a\nb\nc\nd\n", State::new());
+
+    buffer.shrink_to_fit();
+    assert_eq!(buffer.line_count(), 5);
+    assert_eq!(
+      buffer.heap_size(),
+      buffer.len() * mem::size_of::<usize>()
+        + (2 * buffer.len() + 2 * buffer.line_count()) * mem::size_of::<u32>()
+    );
+  }
+
+  #[test]
+  fn test_token_buffer_modes() {
+    assert_rebuilds(
+      "a\r\n\tb\rc\r\n\r\n\t\td",
+      State::new()
+        .with_newline_mode(NewlineMode::Crlf)
+        .with_column_mode(ColumnMode::Visual(4)),
+    );
+    assert_rebuilds(
+      "😀 a\n😀😀 b",
+      State::new().with_column_mode(ColumnMode::Utf16),
+    );
+  }
+}
@@ -0,0 +1,63 @@
+use super::token_buffer::Cursor;
+use super::{Token, TokenBuffer};
+
+/// Rebuilds the tokens of a `TokenBuffer` in order, walking the source once.
+pub struct TokenBufferIter<'a, 's, K, F>
+where
+  K: 'a,
+{
+  buffer: &'a TokenBuffer<K>,
+  source: &'s str,
+  cursor: Option<Cursor<'s>>,
+  index: usize,
+  to_value: F,
+}
+
+impl<'a, 's, K, F> TokenBufferIter<'a, 's, K, F>
+where
+  K: 'a,
+{
+  #[inline]
+  pub(crate) fn new(buffer: &'a TokenBuffer<K>, source: &'s str, to_value: F) -> Self {
+    TokenBufferIter {
+      buffer,
+      source,
+      cursor: None,
+      index: 0,
+      to_value,
+    }
+  }
+}
+
+impl<'a, 's, K, T, F> Iterator for TokenBufferIter<'a, 's, K, F>
+where
+  K: 'a,
+  F: FnMut(&K, &'s str) -> T,
+{
+  type Item = Token<T>;
+
+  #[inline]
+  fn next(&mut self) -> Option<Self::Item> {
+    let range = self.buffer.range(self.index)?;
+    let kind = &self.buffer.kinds()[self.index];
+
+    // tokens behind the cursor, which are out of order, start over from the
+    // line index
+    let cursor = match self.cursor {
+      Some(ref mut cursor) if cursor.index() <= self.buffer.start(self.index) => cursor,
+      _ => self
+        .cursor
+        .insert(self.buffer.cursor(self.source, range.start)),
+    };
+    let (meta, lexeme) = cursor.read_token(range)?;
+
+    self.index += 1;
+    Some(Token::new(meta, (self.to_value)(kind, lexeme)))
+  }
+
+  #[inline]
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    let len = self.buffer.len() - self.index;
+    (len, Some(len))
+  }
+}
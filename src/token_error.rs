@@ -6,16 +6,10 @@ pub struct TokenError<E> {
   error: E,
 }
 
-unsafe impl<E> Send for TokenError<E> where E: Send {}
-unsafe impl<E> Sync for TokenError<E> where E: Sync {}
-
 impl<E> TokenError<E> {
   #[inline(always)]
   pub fn new(meta: TokenMeta, error: E) -> Self {
-    TokenError {
-      meta: meta,
-      error: error,
-    }
+    TokenError { meta, error }
   }
 
   #[inline(always)]
@@ -0,0 +1,82 @@
+use alloc::collections::VecDeque;
+
+use peek_nth::{IteratorExt, PeekableNth};
+
+use super::read::read_reader;
+use super::{ReaderResult, Readers, State};
+
+pub struct TokenIter<'a, T, E, I>
+where
+  T: 'a,
+  E: 'a,
+  I: 'a + IteratorExt<Item = char>,
+{
+  pub(crate) readers: &'a Readers<T, E>,
+  pub(crate) state: State,
+  pub(crate) input: PeekableNth<I>,
+  queue: VecDeque<Result<T, E>>,
+}
+
+impl<'a, T, E, I> From<(&'a Readers<T, E>, I)> for TokenIter<'a, T, E, I>
+where
+  T: 'a,
+  E: 'a,
+  I: 'a + IteratorExt<Item = char>,
+{
+  #[inline(always)]
+  fn from((readers, iter): (&'a Readers<T, E>, I)) -> Self {
+    Self::new(readers, iter)
+  }
+}
+
+impl<'a, T, E, I> TokenIter<'a, T, E, I>
+where
+  T: 'a,
+  E: 'a,
+  I: 'a + IteratorExt<Item = char>,
+{
+  #[inline(always)]
+  pub fn new(readers: &'a Readers<T, E>, iter: I) -> Self {
+    Self::new_with_state(readers, iter, State::new())
+  }
+
+  #[inline(always)]
+  pub fn new_with_state(readers: &'a Readers<T, E>, iter: I, state: State) -> Self {
+    TokenIter {
+      readers,
+      state,
+      input: iter.peekable_nth(),
+      queue: VecDeque::new(),
+    }
+  }
+}
+
+impl<'a, T, E, I> Iterator for TokenIter<'a, T, E, I>
+where
+  T: 'a,
+  E: 'a,
+  I: 'a + IteratorExt<Item = char>,
+{
+  type Item = Result<T, E>;
+
+  #[inline]
+  fn next(&mut self) -> Option<Self::Item> {
+    if let Some(result) = self.queue.pop_front() {
+      return Some(result);
+    }
+
+    let queue = &mut self.queue;
+
+    read_reader(self.readers, &mut self.input, &mut self.state).map(|(_, result)| match result {
+      ReaderResult::Some(token) => Ok(token),
+      ReaderResult::Err(error) => Err(error),
+      ReaderResult::Many(results) => {
+        let mut results = results.into_iter();
+        let result = results.next().expect("non empty results");
+        queue.extend(results);
+        result
+      }
+      ReaderResult::Empty | ReaderResult::None => unreachable!(),
+    })
+  }
+}
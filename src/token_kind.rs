@@ -0,0 +1,51 @@
+use super::{Token, TokenError};
+
+/// Classifies token values so generic tooling can work on them without
+/// matching on the value type, `#[derive(TokenKind)]` numbers the variants of
+/// an enum in declaration order and names them after the variant.
+pub trait TokenKind {
+  fn kind_id(&self) -> usize;
+  fn kind_name(&self) -> &'static str;
+}
+
+impl<T> TokenKind for Token<T>
+where
+  T: TokenKind,
+{
+  #[inline(always)]
+  fn kind_id(&self) -> usize {
+    self.value().kind_id()
+  }
+  #[inline(always)]
+  fn kind_name(&self) -> &'static str {
+    self.value().kind_name()
+  }
+}
+
+impl<E> TokenKind for TokenError<E>
+where
+  E: TokenKind,
+{
+  #[inline(always)]
+  fn kind_id(&self) -> usize {
+    self.error().kind_id()
+  }
+  #[inline(always)]
+  fn kind_name(&self) -> &'static str {
+    self.error().kind_name()
+  }
+}
+
+impl<T> TokenKind for &T
+where
+  T: TokenKind + ?Sized,
+{
+  #[inline(always)]
+  fn kind_id(&self) -> usize {
+    (**self).kind_id()
+  }
+  #[inline(always)]
+  fn kind_name(&self) -> &'static str {
+    (**self).kind_name()
+  }
+}
@@ -10,9 +10,6 @@ pub struct TokenMeta {
   line_end: u64,
 }
 
-unsafe impl Send for TokenMeta {}
-unsafe impl Sync for TokenMeta {}
-
 impl TokenMeta {
   #[inline]
   pub fn new(
@@ -33,12 +30,12 @@ impl TokenMeta {
     );
 
     TokenMeta {
-      index_start: index_start,
-      index_end: index_end,
-      col_start: col_start,
-      col_end: col_end,
-      line_start: line_start,
-      line_end: line_end,
+      index_start,
+      index_end,
+      col_start,
+      col_end,
+      line_start,
+      line_end,
     }
   }
 
@@ -54,6 +51,19 @@ impl TokenMeta {
     )
   }
 
+  /// A zero width meta at `state`, for tokens that stand for no input.
+  #[inline(always)]
+  pub fn new_point_meta(state: &State) -> TokenMeta {
+    TokenMeta {
+      index_start: state.index() as u64,
+      index_end: state.index() as u64,
+      col_start: state.col(),
+      col_end: state.col(),
+      line_start: state.row(),
+      line_end: state.row(),
+    }
+  }
+
   #[inline(always)]
   pub fn index_start(&self) -> u64 {
     self.index_start
@@ -95,6 +105,11 @@ impl TokenMeta {
     self.index_end - self.index_start
   }
 
+  #[inline(always)]
+  pub fn is_empty(&self) -> bool {
+    self.index_end == self.index_start
+  }
+
   #[inline(always)]
   pub fn line_count(&self) -> u64 {
     self.line_end - self.line_start
@@ -0,0 +1,198 @@
+use alloc::string::String;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::fmt;
+use std::sync::Mutex;
+
+use super::{Attempt, AttemptResult, State};
+
+struct Entry {
+  index: usize,
+  name: String,
+  start: State,
+  end: State,
+  result: AttemptResult,
+}
+
+/// Records reader attempts through `Readers::with_observer` and prints them
+/// as a decision log, every position followed by the readers tried there.
+///
+/// ```
+/// use std::sync::Arc;
+///
+/// use lexer::readers::WhitespaceReader;
+/// use lexer::{char_class, ReadersBuilder, Trace};
+///
+/// let trace = Arc::new(Trace::new());
+/// let readers = ReadersBuilder::<String, ()>::new()
+///   .add(WhitespaceReader::new())
+///   .add(char_class(char::is_alphabetic, |string, _| string))
+///   .build()
+///   .with_observer(trace.observer());
+///
+/// assert_eq!(readers.read("a b".chars()).count(), 2);
+/// assert_eq!(
+///   trace.render_at(1),
+///   "1:2 index 1\n  #0 WhitespaceReader empty 1:3\n"
+/// );
+/// ```
+#[derive(Default)]
+pub struct Trace {
+  entries: Mutex<Vec<Entry>>,
+}
+
+impl Trace {
+  #[inline]
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// An observer recording into this trace, for `Readers::with_observer`.
+  #[inline]
+  pub fn observer(self: &Arc<Self>) -> impl 'static + Fn(&Attempt) + Send + Sync {
+    let trace = self.clone();
+    move |attempt| trace.record(attempt)
+  }
+
+  #[inline]
+  pub fn record(&self, attempt: &Attempt) {
+    self.entries().push(Entry {
+      index: attempt.index(),
+      name: attempt.name().into(),
+      start: attempt.start().clone(),
+      end: attempt.end().clone(),
+      result: attempt.result(),
+    });
+  }
+
+  #[inline]
+  pub fn len(&self) -> usize {
+    self.entries().len()
+  }
+
+  #[inline]
+  pub fn is_empty(&self) -> bool {
+    self.len() == 0
+  }
+
+  #[inline]
+  pub fn clear(&self) {
+    self.entries().clear();
+  }
+
+  /// The decision log of the attempts starting at char `index`.
+  #[inline]
+  pub fn render_at(&self, index: usize) -> String {
+    let mut output = String::new();
+    let _ = write_entries(
+      &mut output,
+      self
+        .entries()
+        .iter()
+        .filter(|entry| entry.start.index() == index),
+    );
+    output
+  }
+
+  #[inline]
+  fn entries(&self) -> std::sync::MutexGuard<'_, Vec<Entry>> {
+    self
+      .entries
+      .lock()
+      .unwrap_or_else(|error| error.into_inner())
+  }
+}
+
+impl fmt::Display for Trace {
+  #[inline]
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write_entries(f, self.entries().iter())
+  }
+}
+
+impl fmt::Debug for Trace {
+  #[inline]
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    f.debug_struct("Trace").field("len", &self.len()).finish()
+  }
+}
+
+/// Writes a header whenever the position changes, then a line per attempt
+/// with the reader's index, name, result and where it left its state.
+#[inline]
+fn write_entries<'a, W, I>(output: &mut W, entries: I) -> fmt::Result
+where
+  W: fmt::Write,
+  I: Iterator<Item = &'a Entry>,
+{
+  let mut position = None;
+
+  for entry in entries {
+    if position != Some(entry.start.index()) {
+      position = Some(entry.start.index());
+      writeln!(
+        output,
+        "{}:{} index {}",
+        entry.start.row(),
+        entry.start.col(),
+        entry.start.index()
+      )?;
+    }
+    writeln!(
+      output,
+      "  #{} {} {} {}:{}",
+      entry.index,
+      entry.name,
+      entry.result,
+      entry.end.row(),
+      entry.end.col()
+    )?;
+  }
+  Ok(())
+}
+
+#[cfg(test)]
+mod test {
+  use alloc::string::ToString;
+
+  use super::super::{ReaderResult, ReadersBuilder};
+  use super::*;
+
+  #[test]
+  fn test_trace() {
+    let trace = Arc::new(Trace::new());
+    let readers = ReadersBuilder::<char, ()>::new()
+      .add_fn(|_, input, _, next| {
+        // advances before giving up
+        input.read(next);
+        ReaderResult::None
+      })
+      .add_fn(|_, input, _, next| match input.read(next) {
+        Some(ch) if ch != ' ' => ReaderResult::Some(ch),
+        Some(_) => ReaderResult::Empty,
+        None => ReaderResult::None,
+      })
+      .build()
+      .with_observer(trace.observer());
+
+    assert_eq!(readers.read("a\n b".chars()).count(), 3);
+    assert_eq!(trace.len(), 8);
+    assert_eq!(
+      trace.render_at(2),
+      concat!(
+        "2:1 index 2\n",
+        "  #0 test_trace none 2:2\n",
+        "  #1 test_trace empty 2:2\n",
+      )
+    );
+    assert!(trace.to_string().starts_with(concat!(
+      "1:1 index 0\n",
+      "  #0 test_trace none 1:2\n",
+      "  #1 test_trace some 1:2\n",
+      "1:2 index 1\n",
+    )));
+
+    trace.clear();
+    assert!(trace.is_empty());
+  }
+}
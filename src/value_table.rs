@@ -0,0 +1,196 @@
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::marker::PhantomData;
+use std::sync::RwLock;
+
+/// An index of a value in a `ValueTable`.
+pub struct ValueId<T>(u32, PhantomData<fn() -> T>);
+
+impl<T> ValueId<T> {
+  #[inline(always)]
+  pub fn index(&self) -> usize {
+    self.0 as usize
+  }
+}
+
+impl<T> Clone for ValueId<T> {
+  #[inline(always)]
+  fn clone(&self) -> Self {
+    *self
+  }
+}
+
+impl<T> Copy for ValueId<T> {}
+
+impl<T> PartialEq for ValueId<T> {
+  #[inline(always)]
+  fn eq(&self, other: &Self) -> bool {
+    self.0 == other.0
+  }
+}
+
+impl<T> Eq for ValueId<T> {}
+
+impl<T> Hash for ValueId<T> {
+  #[inline(always)]
+  fn hash<H: Hasher>(&self, state: &mut H) {
+    self.0.hash(state)
+  }
+}
+
+impl<T> fmt::Debug for ValueId<T> {
+  #[inline]
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    f.debug_tuple("ValueId").field(&self.0).finish()
+  }
+}
+
+/// Append only storage for token values, so tokens can carry a small
+/// `ValueId` instead of owning their value, and readers can push values
+/// while lexing. `push` takes the write lock and `get` clones the value out,
+/// `with` borrows it under the read lock instead. Values are only dropped
+/// with the table, and it can be shared between threads reading at once. See
+/// `Arena` to allocate values in a bump arena instead.
+#[derive(Debug)]
+pub struct ValueTable<T> {
+  values: RwLock<Vec<T>>,
+}
+
+impl<T> Default for ValueTable<T> {
+  #[inline]
+  fn default() -> Self {
+    ValueTable {
+      values: RwLock::new(Vec::new()),
+    }
+  }
+}
+
+impl<T> ValueTable<T> {
+  #[inline]
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  #[inline]
+  pub fn with_capacity(capacity: usize) -> Self {
+    ValueTable {
+      values: RwLock::new(Vec::with_capacity(capacity)),
+    }
+  }
+
+  #[inline]
+  pub fn push(&self, value: T) -> ValueId<T> {
+    let mut values = self
+      .values
+      .write()
+      .unwrap_or_else(|error| error.into_inner());
+    let index = u32::try_from(values.len()).expect("a value table holds at most u32::MAX values");
+    let id = ValueId(index, PhantomData);
+
+    values.push(value);
+    id
+  }
+
+  /// Calls `f` with the value of `id`, if it is in this table.
+  #[inline]
+  pub fn with<F, R>(&self, id: ValueId<T>, f: F) -> Option<R>
+  where
+    F: FnOnce(&T) -> R,
+  {
+    self
+      .values
+      .read()
+      .unwrap_or_else(|error| error.into_inner())
+      .get(id.index())
+      .map(f)
+  }
+
+  #[inline]
+  pub fn get(&self, id: ValueId<T>) -> Option<T>
+  where
+    T: Clone,
+  {
+    self.with(id, T::clone)
+  }
+
+  #[inline]
+  pub fn len(&self) -> usize {
+    self
+      .values
+      .read()
+      .unwrap_or_else(|error| error.into_inner())
+      .len()
+  }
+
+  #[inline]
+  pub fn is_empty(&self) -> bool {
+    self.len() == 0
+  }
+
+  /// The values in the order they were allocated, indexed by `ValueId::index`.
+  #[inline]
+  pub fn into_vec(self) -> Vec<T> {
+    self
+      .values
+      .into_inner()
+      .unwrap_or_else(|error| error.into_inner())
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use alloc::string::String;
+  use alloc::sync::Arc;
+
+  use super::super::{ReaderResult, ReadersBuilder, Token, TokenMeta};
+  use super::*;
+
+  #[test]
+  fn test_value_table() {
+    let table = ValueTable::new();
+    let a = table.push(String::from("a"));
+    let b = table.push(String::from("b"));
+
+    assert_ne!(a, b);
+    assert_eq!(table.get(b).as_deref(), Some("b"));
+    assert_eq!(table.with(a, String::len), Some(1));
+    assert_eq!(table.len(), 2);
+    assert_eq!(table.into_vec(), ["a", "b"]);
+  }
+
+  #[test]
+  fn test_reader_value_table() {
+    let table = Arc::new(ValueTable::<String>::new());
+    let reader_values = table.clone();
+    let readers = ReadersBuilder::<Token<ValueId<String>>, ()>::new()
+      .add_fn(move |_, input, current, next| {
+        let mut string = String::new();
+
+        while let Some(ch) = input.read(next) {
+          if ch == ' ' {
+            break;
+          }
+          string.push(ch);
+        }
+
+        if string.is_empty() {
+          ReaderResult::None
+        } else {
+          ReaderResult::Some(Token::new(
+            TokenMeta::new_state_meta(current, next),
+            reader_values.push(string),
+          ))
+        }
+      })
+      .build();
+    let ids: Vec<_> = readers
+      .read("ab cd".chars())
+      .map(|token| *token.unwrap().value())
+      .collect();
+
+    assert_eq!(ids.len(), 2);
+    assert_eq!(table.get(ids[1]).as_deref(), Some("cd"));
+  }
+}
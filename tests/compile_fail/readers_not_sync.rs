@@ -0,0 +1,10 @@
+extern crate lexer;
+
+use lexer::ReadersBuilder;
+
+fn shared<R: Send + Sync>(_: &R) {}
+
+fn main() {
+  let readers = ReadersBuilder::<(), ()>::new().build();
+  shared(&readers);
+}
@@ -0,0 +1,23 @@
+extern crate lexer;
+
+use lexer::{Input, Reader, ReaderResult, Readers, State, SyncReadersBuilder};
+use std::cell::Cell;
+
+struct CountingReader(Cell<usize>);
+
+impl Reader<(), ()> for CountingReader {
+  fn read(
+    &self,
+    _: &Readers<(), ()>,
+    _: &mut dyn Input,
+    _: &State,
+    _: &mut State,
+  ) -> ReaderResult<(), ()> {
+    self.0.set(self.0.get() + 1);
+    ReaderResult::None
+  }
+}
+
+fn main() {
+  SyncReadersBuilder::new().add(CountingReader(Cell::new(0)));
+}
@@ -0,0 +1,13 @@
+extern crate lexer;
+
+use lexer::{ReaderResult, SyncReadersBuilder};
+use std::rc::Rc;
+
+fn main() {
+  let rc = Rc::new(());
+
+  SyncReadersBuilder::<(), ()>::new().add_fn(move |_, _, _, _| {
+    let _ = &rc;
+    ReaderResult::None
+  });
+}